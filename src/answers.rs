@@ -0,0 +1,284 @@
+use crate::{bail, format_err, Context, Error, Input, Part};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The default location of the answers registry.
+pub const DEFAULT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/input/answers.txt");
+
+/// Identifies the answer to one part of a puzzle for a specific input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Key {
+    pub day: u32,
+    pub part: Part,
+    /// Fingerprint of the input, see [`Input::fingerprint`].
+    pub input: u64,
+}
+
+/// An expected answer.
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Name of the input the answer was recorded for. Only informational.
+    name: String,
+    answer: String,
+}
+
+/// The outcome of checking an answer against the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// The answer matches the expected answer.
+    Pass,
+    /// The answer differs from the expected answer.
+    Fail { expected: String },
+    /// There is no expected answer for this input.
+    Unknown,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Status::Pass => "pass".fmt(fmt),
+            Status::Fail { .. } => "FAIL".fmt(fmt),
+            Status::Unknown => "unknown".fmt(fmt),
+        }
+    }
+}
+
+/// A registry of expected answers, stored in a data file.
+///
+/// The file has one answer per line, with tab-separated columns for the day, the part, the input
+/// fingerprint, the input name and the answer. Tabs, newlines and backslashes in answers are
+/// escaped. Empty lines and lines starting with `#` are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+/// use aoc2018::answers::{Answers, Status};
+///
+/// fn main() -> Result<(), Error> {
+///     let input = Input::bundled("day1.txt", "+1\n+2\n");
+///
+///     let mut answers = Answers::default();
+///     assert_eq!(answers.status(1, Part::One, &input, "3"), Status::Unknown);
+///
+///     answers.insert(1, Part::One, &input, "3");
+///     assert_eq!(answers.status(1, Part::One, &input, "3"), Status::Pass);
+///     assert!(answers.check(1, Part::One, &input, 4).is_err());
+///
+///     let answers = Answers::parse(&answers.to_string())?;
+///     assert_eq!(answers.status(1, Part::One, &input, "3"), Status::Pass);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Answers {
+    entries: BTreeMap<Key, Entry>,
+}
+
+impl Answers {
+    /// Load the registry from the given path.
+    ///
+    /// A missing file is treated as an empty registry.
+    pub fn load(path: impl AsRef<Path>) -> Result<Answers, Error> {
+        let path = path.as_ref();
+
+        let data = match fs::read_to_string(path) {
+            Ok(data) => data,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(Answers::default()),
+            Err(e) => {
+                return Err(Error::from(e))
+                    .with_context(|| format!("failed to read answers: {}", path.display()))
+            }
+        };
+
+        Self::parse(&data).with_context(|| format!("bad answers in: {}", path.display()))
+    }
+
+    /// Load the registry from its default location.
+    pub fn load_default() -> Result<Answers, Error> {
+        Self::load(Self::default_path())
+    }
+
+    /// The default location of the registry.
+    pub fn default_path() -> PathBuf {
+        PathBuf::from(DEFAULT_PATH)
+    }
+
+    /// Parse the registry from a string.
+    pub fn parse(data: &str) -> Result<Answers, Error> {
+        let mut entries = BTreeMap::new();
+
+        for (n, line) in data.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let cols = line.splitn(5, '\t').collect::<Vec<_>>();
+
+            let (day, part, input, name, answer) = match cols.as_slice() {
+                [day, part, input, name, answer] => (day, part, input, name, answer),
+                _ => bail!("expected 5 columns on line {}", n + 1),
+            };
+
+            let key = Key {
+                day: str::parse(day)
+                    .map_err(|e| format_err!("bad day on line {}: {}", n + 1, e))?,
+                part: str::parse(part)
+                    .map_err(|e| format_err!("bad part on line {}: {}", n + 1, e))?,
+                input: u64::from_str_radix(input, 16)
+                    .map_err(|e| format_err!("bad input hash on line {}: {}", n + 1, e))?,
+            };
+
+            let entry = Entry {
+                name: name.to_string(),
+                answer: unescape(answer),
+            };
+
+            entries.insert(key, entry);
+        }
+
+        Ok(Answers { entries })
+    }
+
+    /// Save the registry to the given path.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        fs::write(path, self.to_string())
+            .with_context(|| format!("failed to write answers: {}", path.display()))
+    }
+
+    /// Get the expected answer for the given input, if known.
+    pub fn get(&self, day: u32, part: Part, input: &Input) -> Option<&str> {
+        let key = Key {
+            day,
+            part,
+            input: input.fingerprint(),
+        };
+
+        self.entries.get(&key).map(|e| e.answer.as_str())
+    }
+
+    /// Record the expected answer for the given input.
+    pub fn insert(&mut self, day: u32, part: Part, input: &Input, answer: impl fmt::Display) {
+        let key = Key {
+            day,
+            part,
+            input: input.fingerprint(),
+        };
+
+        let entry = Entry {
+            name: input.name().to_string(),
+            answer: answer.to_string(),
+        };
+
+        self.entries.insert(key, entry);
+    }
+
+    /// Compare an answer against the expected answer.
+    pub fn status(&self, day: u32, part: Part, input: &Input, answer: impl fmt::Display) -> Status {
+        match self.get(day, part, input) {
+            Some(expected) if expected == answer.to_string() => Status::Pass,
+            Some(expected) => Status::Fail {
+                expected: expected.to_string(),
+            },
+            None => Status::Unknown,
+        }
+    }
+
+    /// Check an answer, raising an error if it differs from the expected answer.
+    pub fn check(
+        &self,
+        day: u32,
+        part: Part,
+        input: &Input,
+        answer: impl fmt::Display,
+    ) -> Result<Status, Error> {
+        let answer = answer.to_string();
+
+        match self.status(day, part, input, &answer) {
+            Status::Fail { expected } => bail!(
+                "day {}, part {}: expected `{}`, but got `{}`",
+                day,
+                part,
+                expected,
+                answer
+            ),
+            status => Ok(status),
+        }
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "# day\tpart\tinput hash\tinput name\tanswer")?;
+
+        for (key, entry) in &self.entries {
+            writeln!(
+                fmt,
+                "{}\t{}\t{:016x}\t{}\t{}",
+                key.day,
+                key.part,
+                key.input,
+                entry.name,
+                escape(&entry.answer)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Escape an answer so that it fits on a single line.
+pub fn escape(s: &str) -> String {
+    let mut out = String::new();
+
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::new();
+    let mut it = s.chars();
+
+    while let Some(c) = it.next() {
+        match (c, it.clone().next()) {
+            ('\\', Some('n')) => out.push('\n'),
+            ('\\', Some('t')) => out.push('\t'),
+            ('\\', Some('\\')) => out.push('\\'),
+            (c, _) => {
+                out.push(c);
+                continue;
+            }
+        }
+
+        it.next();
+    }
+
+    out
+}
+
+/// Check both answers to a day against the registry in its default location.
+///
+/// Answers for inputs which are not in the registry are accepted.
+pub fn check(
+    day: u32,
+    input: &Input,
+    part1: impl fmt::Display,
+    part2: impl fmt::Display,
+) -> Result<(), Error> {
+    let answers = Answers::load_default()?;
+    answers.check(day, Part::One, input, part1)?;
+    answers.check(day, Part::Two, input, part2)?;
+    Ok(())
+}
@@ -0,0 +1,302 @@
+use crate::{bail, format_err, Context, Error, Input, Part, Solution};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Type-erased implementation of a variant.
+type RunFn<I> = dyn Fn(&I) -> Result<String, Error>;
+
+/// An alternative implementation of one part of a solution, which is benchmarked alongside it.
+pub struct Variant<I> {
+    /// The part the variant solves.
+    pub part: Part,
+    /// The name of the variant.
+    pub name: &'static str,
+    run: Box<RunFn<I>>,
+}
+
+impl<I> Variant<I> {
+    /// Construct a new variant.
+    pub fn new<F, T>(part: Part, name: &'static str, run: F) -> Variant<I>
+    where
+        F: 'static + Fn(&I) -> Result<T, Error>,
+        T: fmt::Display,
+    {
+        Variant {
+            part,
+            name,
+            run: Box::new(move |input| run(input).map(|a| a.to_string())),
+        }
+    }
+
+    /// Run the variant, returning the displayed answer.
+    pub fn run(&self, input: &I) -> Result<String, Error> {
+        (self.run)(input)
+    }
+}
+
+/// Options for benchmarking.
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The number of times to run each part.
+    pub runs: usize,
+    /// Stop running a part early once it has spent this much time, as long as it ran once.
+    pub budget: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            runs: 10,
+            budget: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Repeated timings of parsing, a part, or a variant of a part.
+#[derive(Debug, Clone)]
+pub struct Measurement {
+    /// What was measured, like `parse`, `part1` or `part1/naive`.
+    pub name: String,
+    /// The duration of each run, in sorted order.
+    pub samples: Vec<Duration>,
+}
+
+impl Measurement {
+    /// The median duration.
+    pub fn median(&self) -> Duration {
+        median(&self.samples)
+    }
+
+    /// The median absolute deviation from the median.
+    pub fn spread(&self) -> Duration {
+        let median = self.median();
+
+        let mut deviations = self
+            .samples
+            .iter()
+            .map(|s| s.abs_diff(median))
+            .collect::<Vec<_>>();
+
+        deviations.sort();
+        self::median(&deviations)
+    }
+
+    /// The fastest run.
+    pub fn min(&self) -> Duration {
+        self.samples.first().cloned().unwrap_or_default()
+    }
+
+    /// The slowest run.
+    pub fn max(&self) -> Duration {
+        self.samples.last().cloned().unwrap_or_default()
+    }
+}
+
+/// Median of sorted durations.
+fn median(sorted: &[Duration]) -> Duration {
+    match sorted.len() {
+        0 => Duration::default(),
+        n if n % 2 == 0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+        n => sorted[n / 2],
+    }
+}
+
+/// Time the given function repeatedly.
+fn measure(
+    name: String,
+    options: &Options,
+    mut f: impl FnMut() -> Result<(), Error>,
+) -> Result<Measurement, Error> {
+    let mut samples = Vec::new();
+    let mut total = Duration::default();
+
+    while samples.len() < options.runs.max(1) {
+        if !samples.is_empty() && total >= options.budget {
+            break;
+        }
+
+        let start = Instant::now();
+        f().with_context(|| format!("failed to run {}", name))?;
+        let duration = start.elapsed();
+
+        total += duration;
+        samples.push(duration);
+    }
+
+    samples.sort();
+    Ok(Measurement { name, samples })
+}
+
+/// Benchmark parsing, the given parts, and all variants of those parts.
+///
+/// Variants are checked to give the same answer as the part they are an alternative to.
+pub(crate) fn bench<S>(
+    input: &str,
+    parts: &[Part],
+    options: &Options,
+) -> Result<Vec<Measurement>, Error>
+where
+    S: Solution,
+{
+    crate::solution::validate::<S>(input)?;
+    let mut measurements = Vec::new();
+
+    measurements.push(measure(String::from("parse"), options, || {
+        S::parse(input).map(drop)
+    })?);
+
+    let input = S::parse(input)?;
+    let variants = S::variants();
+
+    for part in parts.iter().cloned() {
+        let run = |input: &S::Input| match part {
+            Part::One => S::part1(input).map(|a| a.to_string()),
+            Part::Two => S::part2(input).map(|a| a.to_string()),
+        };
+
+        let expected = run(&input)?;
+
+        measurements.push(measure(format!("part{}", part), options, || {
+            run(&input).map(drop)
+        })?);
+
+        for variant in variants.iter().filter(|v| v.part == part) {
+            let name = format!("part{}/{}", part, variant.name);
+            let answer = variant.run(&input)?;
+
+            if answer != expected {
+                bail!(
+                    "{} disagrees with part {}: `{}` != `{}`",
+                    name,
+                    part,
+                    answer,
+                    expected
+                );
+            }
+
+            measurements.push(measure(name, options, || variant.run(&input).map(drop))?);
+        }
+    }
+
+    Ok(measurements)
+}
+
+/// Median timings from an earlier benchmark, used to spot performance regressions.
+///
+/// Measurements are kept per input, since timings on different inputs can't be compared. The file
+/// has one measurement per line, with tab-separated columns for the day, the input fingerprint
+/// (see [`Input::fingerprint`]), the name of the measurement, and the median in nanoseconds. Empty
+/// lines and lines starting with `#` are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+/// use aoc2018::bench::{Baseline, Measurement};
+/// use std::time::Duration;
+///
+/// fn main() -> Result<(), Error> {
+///     let input = Input::bundled("day5.txt", "dabAcCaCBAcCcaDA");
+///     let mut baseline = Baseline::default();
+///
+///     baseline.insert(5, &input, &Measurement {
+///         name: String::from("part1"),
+///         samples: vec![Duration::from_millis(1), Duration::from_millis(3)],
+///     });
+///
+///     let baseline = Baseline::parse(&baseline.to_string())?;
+///     assert_eq!(baseline.get(5, &input, "part1"), Some(Duration::from_millis(2)));
+///     assert_eq!(baseline.get(5, &input, "part2"), None);
+///
+///     // measurements on other inputs aren't comparable.
+///     let other = Input::bundled("big.txt", "aA");
+///     assert_eq!(baseline.get(5, &other, "part1"), None);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Baseline {
+    entries: BTreeMap<(u32, u64, String), Duration>,
+}
+
+impl Baseline {
+    /// Load a baseline from the given path.
+    pub fn load(path: impl AsRef<Path>) -> Result<Baseline, Error> {
+        let path = path.as_ref();
+
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("failed to read baseline: {}", path.display()))?;
+
+        Self::parse(&data).with_context(|| format!("bad baseline in: {}", path.display()))
+    }
+
+    /// Parse a baseline from a string.
+    pub fn parse(data: &str) -> Result<Baseline, Error> {
+        let mut entries = BTreeMap::new();
+
+        for (n, line) in data.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let cols = line.split('\t').collect::<Vec<_>>();
+
+            let (day, input, name, median) = match cols.as_slice() {
+                [day, input, name, median] => (day, input, name, median),
+                _ => bail!("expected 4 columns on line {}", n + 1),
+            };
+
+            let day =
+                str::parse(day).map_err(|e| format_err!("bad day on line {}: {}", n + 1, e))?;
+            let input = u64::from_str_radix(input, 16)
+                .map_err(|e| format_err!("bad input hash on line {}: {}", n + 1, e))?;
+            let median = str::parse(median)
+                .map_err(|e| format_err!("bad median on line {}: {}", n + 1, e))?;
+
+            entries.insert((day, input, name.to_string()), Duration::from_nanos(median));
+        }
+
+        Ok(Baseline { entries })
+    }
+
+    /// Save the baseline to the given path.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        fs::write(path, self.to_string())
+            .with_context(|| format!("failed to write baseline: {}", path.display()))
+    }
+
+    /// Get the median of the given measurement on the given input.
+    pub fn get(&self, day: u32, input: &Input, name: &str) -> Option<Duration> {
+        let key = (day, input.fingerprint(), name.to_string());
+        self.entries.get(&key).cloned()
+    }
+
+    /// Record the median of a measurement on the given input, replacing any earlier one.
+    pub fn insert(&mut self, day: u32, input: &Input, measurement: &Measurement) {
+        let key = (day, input.fingerprint(), measurement.name.clone());
+        self.entries.insert(key, measurement.median());
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(fmt, "# day\tinput\tname\tmedian (ns)")?;
+
+        for ((day, input, name), median) in &self.entries {
+            writeln!(
+                fmt,
+                "{}\t{:016x}\t{}\t{}",
+                day,
+                input,
+                name,
+                median.as_nanos()
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,549 @@
+use aoc2018::*;
+
+use aoc2018::answers::{self, Answers, Status};
+use aoc2018::bench::{self, Baseline};
+use std::env;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const USAGE: &str = "usage: aoc [verify|bench|gen] <day|all> [--part <1|2>] [--input <path>]
+generated input options: [--seed <n>] [--size <n>]
+verify options: [--answers <path>]
+bench options: [--runs <n>] [--baseline <path>] [--save <path>] [--threshold <percent>]";
+
+/// What to do with the selected days.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Print the answers.
+    Run,
+    /// Check the answers against the registry.
+    Verify,
+    /// Benchmark the parts.
+    Bench,
+    /// Print a generated input.
+    Gen,
+}
+
+/// The size of generated inputs if none is specified.
+const DEFAULT_SIZE: usize = 100;
+
+/// Which days to run.
+enum Days {
+    All,
+    One(u32),
+}
+
+struct Args {
+    mode: Mode,
+    days: Days,
+    part: Option<Part>,
+    input: Option<String>,
+    /// Seed and size to generate inputs from, if any.
+    generate: Option<(u64, usize)>,
+    answers: Option<PathBuf>,
+    bench: bench::Options,
+    baseline: Option<PathBuf>,
+    save: Option<PathBuf>,
+    /// Slowdown in percent beyond which a part counts as a regression.
+    threshold: f64,
+}
+
+impl Args {
+    fn parse(mut it: impl Iterator<Item = String>) -> Result<Args, Error> {
+        let mut mode = Mode::Run;
+        let mut days = None;
+        let mut part = None;
+        let mut input = None;
+        let mut seed = None;
+        let mut size = None;
+        let mut answers = None;
+        let mut bench = bench::Options::default();
+        let mut baseline = None;
+        let mut save = None;
+        let mut threshold = 10.0;
+
+        while let Some(arg) = it.next() {
+            match arg.as_str() {
+                "--part" | "-p" => {
+                    let value = it
+                        .next()
+                        .ok_or_else(|| format_err!("missing argument to --part"))?;
+                    part = Some(str::parse(&value)?);
+                }
+                "--input" | "-i" => {
+                    input = Some(
+                        it.next()
+                            .ok_or_else(|| format_err!("missing argument to --input"))?,
+                    );
+                }
+                "--seed" => {
+                    let value = it
+                        .next()
+                        .ok_or_else(|| format_err!("missing argument to --seed"))?;
+                    seed = Some(str::parse(&value)?);
+                }
+                "--size" => {
+                    let value = it
+                        .next()
+                        .ok_or_else(|| format_err!("missing argument to --size"))?;
+                    size = Some(str::parse(&value)?);
+                }
+                "--answers" => {
+                    answers =
+                        Some(PathBuf::from(it.next().ok_or_else(|| {
+                            format_err!("missing argument to --answers")
+                        })?));
+                }
+                "--runs" => {
+                    let value = it
+                        .next()
+                        .ok_or_else(|| format_err!("missing argument to --runs"))?;
+                    bench.runs = str::parse(&value)?;
+                }
+                "--baseline" => {
+                    baseline =
+                        Some(PathBuf::from(it.next().ok_or_else(|| {
+                            format_err!("missing argument to --baseline")
+                        })?));
+                }
+                "--save" => {
+                    save = Some(PathBuf::from(
+                        it.next()
+                            .ok_or_else(|| format_err!("missing argument to --save"))?,
+                    ));
+                }
+                "--threshold" => {
+                    let value = it
+                        .next()
+                        .ok_or_else(|| format_err!("missing argument to --threshold"))?;
+                    threshold = str::parse(&value)?;
+                }
+                "verify" if days.is_none() => mode = Mode::Verify,
+                "bench" if days.is_none() => mode = Mode::Bench,
+                "gen" if days.is_none() => mode = Mode::Gen,
+                "all" => days = Some(Days::All),
+                other => {
+                    let day = str::parse(other.trim_start_matches("day"))
+                        .map_err(|_| format_err!("bad argument `{}`\n{}", other, USAGE))?;
+                    days = Some(Days::One(day));
+                }
+            }
+        }
+
+        let days = days.ok_or_else(|| format_err!("{}", USAGE))?;
+
+        if let (Days::All, Some(_)) = (&days, &input) {
+            bail!("--input can only be used when running a single day");
+        }
+
+        let generate = match (seed, size) {
+            (None, None) if mode != Mode::Gen => None,
+            (seed, size) => Some((seed.unwrap_or_default(), size.unwrap_or(DEFAULT_SIZE))),
+        };
+
+        if generate.is_some() && input.is_some() {
+            bail!("--input can't be used together with generated input");
+        }
+
+        // NB: answers are recorded by input, which would fill the registry with generated inputs.
+        if generate.is_some() && mode == Mode::Verify {
+            bail!("generated input can't be verified");
+        }
+
+        if let (Days::All, Mode::Gen) = (&days, mode) {
+            bail!("gen can only be used with a single day");
+        }
+
+        Ok(Args {
+            mode,
+            days,
+            part,
+            input,
+            generate,
+            answers,
+            bench,
+            baseline,
+            save,
+            threshold,
+        })
+    }
+}
+
+impl Args {
+    /// Load the input to use for the given day.
+    fn input(&self, day: &Day) -> Result<Input, Error> {
+        if let Some((seed, size)) = self.generate {
+            return day
+                .generate(seed, size)
+                .ok_or_else(|| format_err!("day {} has no input generator", day.day));
+        }
+
+        match self.input.as_ref() {
+            Some(path) if path == "-" => Input::from_stdin(),
+            Some(path) => Input::from_path(path),
+            None => Ok(day.input()),
+        }
+    }
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse(env::args().skip(1))?;
+
+    let mut days = match args.days {
+        Days::All => days::all(),
+        Days::One(day) => vec![days::get(day).ok_or_else(|| format_err!("no such day: {}", day))?],
+    };
+
+    // NB: only run the days which can generate input when running all of them.
+    if let (Some(_), Days::All) = (args.generate, &args.days) {
+        days.retain(|day| day.generate(0, 0).is_some());
+    }
+
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+
+    match args.mode {
+        Mode::Run => run(&args, days, &parts),
+        Mode::Verify => verify(&args, days, &parts),
+        Mode::Bench => bench(&args, days, &parts),
+        Mode::Gen => gen(&args, days),
+    }
+}
+
+/// Run the given days and print their answers.
+fn run(args: &Args, days: Vec<Day>, parts: &[Part]) -> Result<(), Error> {
+    let mut failed = 0;
+    let mut total = Duration::default();
+
+    for day in days {
+        let input = args.input(&day)?;
+
+        let answers = match day.run(&input, parts) {
+            Ok(answers) => answers,
+            Err(e) => {
+                println!("day {}: failed to parse `{}`: {}", day.day, input.name(), e);
+                failed += parts.len();
+                continue;
+            }
+        };
+
+        for answer in answers {
+            total += answer.duration;
+
+            match answer.value {
+                Ok(value) if value.contains('\n') => {
+                    println!(
+                        "day {}, part {}: ({:?})",
+                        day.day, answer.part, answer.duration
+                    );
+                    print!("{}", value);
+                }
+                Ok(value) => {
+                    println!(
+                        "day {}, part {}: {} ({:?})",
+                        day.day, answer.part, value, answer.duration
+                    );
+                }
+                Err(e) => {
+                    println!("day {}, part {}: error: {}", day.day, answer.part, e);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    println!("total: {:?}", total);
+
+    if failed > 0 {
+        bail!("{} part(s) failed", failed);
+    }
+
+    Ok(())
+}
+
+/// Run the given days and compare their answers against the registry.
+///
+/// Every part is run even if earlier ones fail or panic, and answers for inputs which are not in
+/// the registry are recorded.
+fn verify(args: &Args, days: Vec<Day>, parts: &[Part]) -> Result<(), Error> {
+    let path = args.answers.clone().unwrap_or_else(Answers::default_path);
+    let mut registry = Answers::load(&path)?;
+
+    let mut failed = 0;
+    let mut recorded = 0;
+
+    println!(
+        "{:>3}  {:>4}  {:<7}  {:<12}  answer",
+        "day", "part", "status", "input"
+    );
+
+    for day in days {
+        let input = args.input(&day)?;
+
+        for (part, value) in run_parts(&day, &input, parts) {
+            let (status, detail) = match value {
+                Ok(value) => match registry.status(day.day, part, &input, &value) {
+                    Status::Pass => (String::from("pass"), answers::escape(&value)),
+                    Status::Fail { expected } => {
+                        failed += 1;
+
+                        let detail = format!(
+                            "{} (expected {})",
+                            answers::escape(&value),
+                            answers::escape(&expected)
+                        );
+
+                        (String::from("FAIL"), detail)
+                    }
+                    Status::Unknown => {
+                        registry.insert(day.day, part, &input, &value);
+                        recorded += 1;
+                        (
+                            String::from("unknown"),
+                            format!("{} (recorded)", answers::escape(&value)),
+                        )
+                    }
+                },
+                Err(e) => {
+                    failed += 1;
+                    (String::from("error"), e)
+                }
+            };
+
+            println!(
+                "{:>3}  {:>4}  {:<7}  {:<12}  {}",
+                day.day,
+                part,
+                status,
+                input.name(),
+                detail
+            );
+        }
+    }
+
+    if recorded > 0 {
+        registry.save(&path)?;
+        println!("recorded {} new answer(s) in {}", recorded, path.display());
+    }
+
+    if failed > 0 {
+        bail!("{} part(s) failed verification", failed);
+    }
+
+    Ok(())
+}
+
+/// Benchmark the given days, optionally comparing against and saving a baseline.
+fn bench(args: &Args, days: Vec<Day>, parts: &[Part]) -> Result<(), Error> {
+    let baseline = match args.baseline.as_ref() {
+        Some(path) => Some(Baseline::load(path)?),
+        None => None,
+    };
+
+    // NB: merge into an existing baseline, so that benchmarking one day keeps the others.
+    let mut save = match args.save.as_ref() {
+        Some(path) if path.is_file() => Some(Baseline::load(path)?),
+        Some(_) => Some(Baseline::default()),
+        None => None,
+    };
+
+    let mut regressions = 0;
+
+    println!(
+        "{:>3}  {:<20}  {:>10}  {:>10}  {:>10}  {:>10}  {:>5}  baseline",
+        "day", "name", "median", "spread", "min", "max", "runs"
+    );
+
+    for day in days {
+        let input = args.input(&day)?;
+        let measurements = day.bench(&input, parts, &args.bench)?;
+
+        for m in measurements {
+            let comparison = match baseline
+                .as_ref()
+                .and_then(|b| b.get(day.day, &input, &m.name))
+            {
+                Some(base) => {
+                    let change = percent_change(base, m.median());
+
+                    // NB: changes within the spread of the measurement are noise.
+                    let significant =
+                        change.abs() > args.threshold && base.abs_diff(m.median()) > m.spread();
+
+                    let verdict = match (significant, change > 0.0) {
+                        (true, true) => {
+                            regressions += 1;
+                            " REGRESSION"
+                        }
+                        (true, false) => " improved",
+                        (false, _) => "",
+                    };
+
+                    format!("{:+.1}%{}", change, verdict)
+                }
+                None if baseline.is_some() => String::from("no baseline"),
+                None => String::new(),
+            };
+
+            println!(
+                "{:>3}  {:<20}  {:>10}  {:>10}  {:>10}  {:>10}  {:>5}  {}",
+                day.day,
+                m.name,
+                format!("{:.2?}", m.median()),
+                format!("{:.2?}", m.spread()),
+                format!("{:.2?}", m.min()),
+                format!("{:.2?}", m.max()),
+                m.samples.len(),
+                comparison,
+            );
+
+            if let Some(save) = save.as_mut() {
+                save.insert(day.day, &input, &m);
+            }
+        }
+    }
+
+    if let (Some(save), Some(path)) = (save.as_ref(), args.save.as_ref()) {
+        save.save(path)?;
+        println!("saved baseline to {}", path.display());
+    }
+
+    if regressions > 0 {
+        bail!("{} regression(s) against the baseline", regressions);
+    }
+
+    Ok(())
+}
+
+/// Print the generated input for the given days.
+fn gen(args: &Args, days: Vec<Day>) -> Result<(), Error> {
+    for day in days {
+        print!("{}", args.input(&day)?.as_str());
+    }
+
+    Ok(())
+}
+
+/// Change from `base` to `current` in percent.
+fn percent_change(base: Duration, current: Duration) -> f64 {
+    let base = base.as_secs_f64();
+
+    if base == 0.0 {
+        return 0.0;
+    }
+
+    (current.as_secs_f64() - base) / base * 100.0
+}
+
+/// Parse the input once, then run each part on its own, so that a part which panics doesn't
+/// prevent the others from running.
+fn run_parts(day: &Day, input: &Input, parts: &[Part]) -> Vec<(Part, Result<String, String>)> {
+    let parsed = match day.parse(input) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let e = format!("failed to parse input: {}", e);
+            return parts.iter().map(|part| (*part, Err(e.clone()))).collect();
+        }
+    };
+
+    let mut values = Vec::new();
+
+    for part in parts.iter().cloned() {
+        let value = match panic::catch_unwind(AssertUnwindSafe(|| day.run_part(&parsed, part))) {
+            Ok(answer) => answer.value.map_err(|e| e.to_string()),
+            Err(panic) => Err(format!("panicked: {}", panic_message(&*panic))),
+        };
+
+        values.push((part, value));
+    }
+
+    values
+}
+
+/// Extract the message from a panic payload.
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> &str {
+    if let Some(s) = panic.downcast_ref::<&str>() {
+        return s;
+    }
+
+    if let Some(s) = panic.downcast_ref::<String>() {
+        return s;
+    }
+
+    "unknown panic"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static PARSED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Panics;
+
+    impl Solution for Panics {
+        type Input = ();
+        type Part1 = u32;
+        type Part2 = u32;
+
+        fn parse(_: &str) -> Result<Self::Input, Error> {
+            Ok(())
+        }
+
+        fn part1(_: &Self::Input) -> Result<u32, Error> {
+            panic!("part 1 is broken")
+        }
+
+        fn part2(_: &Self::Input) -> Result<u32, Error> {
+            Ok(42)
+        }
+    }
+
+    struct CountsParses;
+
+    impl Solution for CountsParses {
+        type Input = ();
+        type Part1 = u32;
+        type Part2 = u32;
+
+        fn parse(_: &str) -> Result<Self::Input, Error> {
+            PARSED.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn part1(_: &Self::Input) -> Result<u32, Error> {
+            Ok(1)
+        }
+
+        fn part2(_: &Self::Input) -> Result<u32, Error> {
+            Ok(2)
+        }
+    }
+
+    #[test]
+    fn test_run_parts_after_panic() {
+        let day = Day::new::<Panics>(1, "panics.txt", "");
+        let values = run_parts(&day, &day.input(), &Part::ALL);
+
+        assert_eq!(
+            values,
+            vec![
+                (Part::One, Err(String::from("panicked: part 1 is broken"))),
+                (Part::Two, Ok(String::from("42"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_parts_parses_once() {
+        let day = Day::new::<CountsParses>(1, "counts.txt", "");
+        let values = run_parts(&day, &day.input(), &Part::ALL);
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(PARSED.load(Ordering::SeqCst), 1);
+    }
+}
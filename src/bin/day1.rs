@@ -1,24 +1,14 @@
+use aoc2018::days::day1::Day1;
 use aoc2018::*;
 
-fn part2(mods: &[i64]) -> Option<i64> {
-    let mut seen = HashSet::new();
-    seen.insert(0);
+fn main() -> Result<(), Error> {
+    let input = load_input!("day1.txt")?;
+    let (part1, part2) = solve::<Day1>(&input)?;
 
-    mods.iter()
-        .cloned()
-        .cycle()
-        .scan(0, |a, b| {
-            *a += b;
-            Some(*a)
-        })
-        .filter(|f| !seen.insert(*f))
-        .next()
-}
+    println!("Part 1: {}", part1);
+    println!("Part 2: {}", part2);
 
-fn main() -> Result<(), Error> {
-    let mods = columns!(input!("day1.txt"), char::is_whitespace, i64);
+    answers::check(1, &input, part1, part2)?;
 
-    assert_eq!(497, mods.iter().cloned().sum::<i64>());
-    assert_eq!(Some(558), part2(&mods));
     Ok(())
 }
@@ -0,0 +1,244 @@
+//! Detection of cycles in repeatedly stepped states, to skip ahead to far away steps.
+
+use crate::Error;
+use hashbrown::HashMap;
+use std::hash::Hash;
+
+/// A cycle in a sequence of states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first step of the cycle.
+    pub start: usize,
+    /// The number of steps before the cycle repeats.
+    pub length: usize,
+    /// How much the offset of the state changes every time the cycle repeats.
+    ///
+    /// This is zero unless the cycle was detected with [`find_drifting`].
+    pub drift: i64,
+}
+
+/// The states visited while looking for a cycle.
+#[derive(Debug, Clone)]
+pub struct Sequence<S> {
+    states: Vec<S>,
+    cycle: Option<Cycle>,
+}
+
+impl<S> Sequence<S> {
+    /// The cycle that was found, if any.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// The number of states that were visited, including the initial one.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Test if no states were visited, which is never the case.
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Get the state at the given step, together with how far its offset has drifted.
+    ///
+    /// Steps past the cycle are mapped onto the equivalent step in the first repetition of it, so
+    /// the drift needs to be added to the offset of the returned state.
+    ///
+    /// Returns `None` if the step is past the end of the sequence and no cycle was found.
+    pub fn get(&self, step: usize) -> Option<(&S, i64)> {
+        if let Some(state) = self.states.get(step) {
+            return Some((state, 0));
+        }
+
+        let Cycle {
+            start,
+            length,
+            drift,
+        } = self.cycle?;
+
+        let repeats = (step - start) / length;
+        let state = self.states.get(start + (step - start) % length)?;
+        Some((state, repeats as i64 * drift))
+    }
+}
+
+/// Step the initial state until the key of a state repeats, or `limit` steps were taken.
+///
+/// The key should be a canonical representation of the state, so that states with the same key
+/// have the same future.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// fn main() -> Result<(), Error> {
+///     let seq = cycle::find(1u64, |n| Ok(n * 3 % 10), |n| *n, usize::MAX)?;
+///
+///     assert_eq!(seq.cycle().map(|c| (c.start, c.length)), Some((0, 4)));
+///     assert_eq!(seq.get(1_000_000_001), Some((&3, 0)));
+///     Ok(())
+/// }
+/// ```
+pub fn find<S, K>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Result<S, Error>,
+    mut key: impl FnMut(&S) -> K,
+    limit: usize,
+) -> Result<Sequence<S>, Error>
+where
+    K: Eq + Hash,
+{
+    find_drifting(initial, &mut step, |s| (key(s), 0), limit)
+}
+
+/// Like [`find`], but for states which can repeat at a different offset.
+///
+/// The key returns the canonical representation of the state without its offset, and the offset.
+/// A repeat at a different offset is a cycle where the offset drifts linearly.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// fn main() -> Result<(), Error> {
+///     // A glider which moves one step to the right every three steps.
+///     let step = |&(phase, x): &(u32, i64)| Ok(((phase + 1) % 3, if phase == 2 { x + 1 } else { x }));
+///     let seq = cycle::find_drifting((0, 0), step, |&(phase, x)| (phase, x), 1000)?;
+///
+///     assert_eq!(seq.cycle().map(|c| (c.start, c.length, c.drift)), Some((0, 3, 1)));
+///
+///     let ((phase, x), drift) = seq.get(301).expect("step");
+///     assert_eq!((*phase, x + drift), (1, 100));
+///     Ok(())
+/// }
+/// ```
+pub fn find_drifting<S, K>(
+    initial: S,
+    mut step: impl FnMut(&S) -> Result<S, Error>,
+    mut key: impl FnMut(&S) -> (K, i64),
+    limit: usize,
+) -> Result<Sequence<S>, Error>
+where
+    K: Eq + Hash,
+{
+    let mut seen = HashMap::new();
+
+    let (k, offset) = key(&initial);
+    seen.insert(k, (0, offset));
+
+    let mut states = vec![initial];
+
+    for n in 1..=limit {
+        let next = match states.last() {
+            Some(last) => step(last)?,
+            None => break,
+        };
+
+        let (k, offset) = key(&next);
+        states.push(next);
+
+        if let Some((start, previous)) = seen.insert(k, (n, offset)) {
+            let cycle = Cycle {
+                start,
+                length: n - start,
+                drift: offset - previous,
+            };
+
+            return Ok(Sequence {
+                states,
+                cycle: Some(cycle),
+            });
+        }
+    }
+
+    Ok(Sequence {
+        states,
+        cycle: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Steps through phases 1 to 3 after a phase 0 which is never repeated, moving ten steps to the
+    /// right every time phase 3 wraps around.
+    fn step(&(phase, x): &(u32, i64)) -> Result<(u32, i64), Error> {
+        Ok(match phase {
+            3 => (1, x + 10),
+            phase => (phase + 1, x),
+        })
+    }
+
+    #[test]
+    fn test_get_past_cycle() -> Result<(), Error> {
+        let seq = find(0u32, |n| Ok(if *n < 4 { n + 1 } else { 2 }), |n| *n, 100)?;
+
+        let cycle = Cycle {
+            start: 2,
+            length: 3,
+            drift: 0,
+        };
+
+        assert_eq!(seq.cycle(), Some(cycle));
+        assert_eq!(seq.len(), 6);
+        assert_eq!(seq.get(5), Some((&2, 0)));
+        assert_eq!(seq.get(6), Some((&3, 0)));
+        assert_eq!(seq.get(100), Some((&4, 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_past_cycle_without_drift() -> Result<(), Error> {
+        // NB: the offset isn't part of the key, so the state at a later step is returned as it was
+        // first seen.
+        let seq = find((0, 0), step, |&(phase, _)| phase, 100)?;
+
+        assert_eq!(
+            seq.cycle().map(|c| (c.start, c.length, c.drift)),
+            Some((1, 3, 0))
+        );
+        assert_eq!(seq.get(100), Some((&(1, 0), 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_past_cycle_with_drift() -> Result<(), Error> {
+        let seq = find_drifting((0, 0), step, |&(phase, x)| (phase, x), 100)?;
+
+        assert_eq!(
+            seq.cycle().map(|c| (c.start, c.length, c.drift)),
+            Some((1, 3, 10))
+        );
+        assert_eq!(seq.get(4), Some((&(1, 10), 0)));
+        assert_eq!(seq.get(100), Some((&(1, 0), 330)));
+        assert_eq!(seq.get(102), Some((&(3, 0), 330)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycle_from_start() -> Result<(), Error> {
+        let seq = find(1u64, |n| Ok(n * 3 % 10), |n| *n, 100)?;
+
+        assert_eq!(seq.cycle().map(|c| (c.start, c.length)), Some((0, 4)));
+        assert_eq!(seq.get(0), Some((&1, 0)));
+        assert_eq!(seq.get(4), Some((&1, 0)));
+        assert_eq!(seq.get(8), Some((&1, 0)));
+        assert_eq!(seq.get(10), Some((&9, 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_cycle() -> Result<(), Error> {
+        let seq = find(0u64, |n| Ok(n + 1), |n| *n, 10)?;
+
+        assert_eq!(seq.cycle(), None);
+        assert_eq!(seq.len(), 11);
+        assert_eq!(seq.get(10), Some((&10, 0)));
+        assert_eq!(seq.get(11), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use crate::*;
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<i64>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(columns!(input.as_bytes(), char::is_whitespace, i64))
+    }
+
+    fn part1(mods: &Self::Input) -> Result<i64, Error> {
+        Ok(mods.iter().cloned().sum::<i64>())
+    }
+
+    fn part2(mods: &Self::Input) -> Result<i64, Error> {
+        part2(mods).ok_or_else(|| format_err!("no frequency is reached twice"))
+    }
+}
+
+fn part2(mods: &[i64]) -> Option<i64> {
+    let mut seen = HashSet::new();
+    seen.insert(0);
+
+    mods.iter()
+        .cloned()
+        .cycle()
+        .scan(0, |a, b| {
+            *a += b;
+            Some(*a)
+        })
+        .find(|f| !seen.insert(*f))
+}
@@ -0,0 +1,84 @@
+use crate::*;
+
+pub struct Day10;
+
+/// A point of light with its position and velocity.
+pub type Point = (na::Vector2<i32>, na::Vector2<i32>);
+
+impl Solution for Day10 {
+    type Input = Vec<Point>;
+    type Part1 = String;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut points = Vec::new();
+
+        for point in scan!(
+            input,
+            "position=<{},{}> velocity=<{},{}>",
+            i32,
+            i32,
+            i32,
+            i32
+        ) {
+            let (x, y, dx, dy) = point?;
+            points.push((na::Vector2::new(x, y), na::Vector2::new(dx, dy)));
+        }
+
+        Ok(points)
+    }
+
+    fn part1(points: &Self::Input) -> Result<String, Error> {
+        Ok(align(points, 10)?.0)
+    }
+
+    fn part2(points: &Self::Input) -> Result<usize, Error> {
+        Ok(align(points, 10)?.1)
+    }
+}
+
+/// Move the points until they line up into a message which is `height` high.
+///
+/// Returns the rendered message, and the number of seconds it took for it to appear.
+pub fn align(points: &[Point], height: i32) -> Result<(String, usize), Error> {
+    use std::fmt::Write;
+
+    let mut points = points.to_vec();
+
+    for i in 1.. {
+        let mut bounds = Bounds2::default();
+
+        for &mut (ref mut pos, ref vel) in &mut points {
+            *pos += *vel;
+            bounds.sample((pos.x, pos.y));
+        }
+
+        match bounds.height() {
+            Some(h) if h == height => {}
+            Some(h) if h < height => bail!("points never line up"),
+            Some(_) => continue,
+            None => bail!("no points"),
+        }
+
+        let by_pos = points.iter().map(|(pos, _)| *pos).collect::<HashSet<_>>();
+        let (_, (x1, _)) = bounds.get().ok_or_else(|| format_err!("no points"))?;
+
+        let mut out = String::new();
+
+        for (x, y) in bounds.iter() {
+            if by_pos.contains(&na::Vector2::new(x, y)) {
+                out.push('#');
+            } else {
+                out.push(' ');
+            }
+
+            if x == x1 {
+                writeln!(out)?;
+            }
+        }
+
+        return Ok((out, i));
+    }
+
+    bail!("points never line up")
+}
@@ -0,0 +1,92 @@
+use crate::*;
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    /// The grid serial number.
+    type Input = i64;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(str::parse(input.trim())?)
+    }
+
+    fn part1(serial: &Self::Input) -> Result<String, Error> {
+        let (x, y, _) = part1(&grid(*serial)).ok_or_else(|| format_err!("no solution found"))?;
+        Ok(format!("{},{}", x, y))
+    }
+
+    fn part2(serial: &Self::Input) -> Result<String, Error> {
+        let (x, y, size, _) =
+            part2(&grid(*serial)).ok_or_else(|| format_err!("no solution found"))?;
+        Ok(format!("{},{},{}", x, y, size))
+    }
+}
+
+/// Calculate the power level of every fuel cell for the given grid serial number.
+///
+/// The cell at `(x, y)` in the puzzle is stored at `(x - 1, y - 1)` in the grid.
+pub fn grid(grid_serial: i64) -> Grid<i64> {
+    Grid::from_fn(300, 300, |(x, y)| {
+        let (x, y) = (x as i64 + 1, y as i64 + 1);
+        let rack_id = x + 10;
+        let mut level = rack_id * y;
+        level += grid_serial;
+        level *= rack_id;
+        level %= 1000;
+        level = (level / 100) % 10;
+        level -= 5;
+        level
+    })
+}
+
+pub fn part1(grid: &Grid<i64>) -> Option<(i64, i64, i64)> {
+    let mut best = None::<(i64, i64, i64)>;
+
+    for y in 0..=(grid.height() - 3) {
+        for x in 0..=(grid.width() - 3) {
+            let mut total = 0;
+
+            for yp in y..(y + 3) {
+                for xp in x..(x + 3) {
+                    total += grid[(xp, yp)];
+                }
+            }
+
+            if best.map(|b| total > b.2).unwrap_or(true) {
+                best = Some((x as i64 + 1, y as i64 + 1, total));
+            }
+        }
+    }
+
+    best
+}
+
+pub fn part2(grid: &Grid<i64>) -> Option<(i64, i64, i64, i64)> {
+    // Total of the square of the current size with its top-left corner at each position.
+    let mut dynamic = Grid::new(grid.width(), grid.height(), 0i64);
+    let mut best = None::<(i64, i64, i64, i64)>;
+
+    for i in 1..=grid.width().min(grid.height()) {
+        for y in 0..=(grid.height() - i) {
+            for x in 0..=(grid.width() - i) {
+                let total = &mut dynamic[(x, y)];
+
+                for yp in y..(y + i) {
+                    *total += grid[(x + i - 1, yp)];
+                }
+
+                for xp in x..(x + i - 1) {
+                    *total += grid[(xp, y + i - 1)];
+                }
+
+                if best.map(|b| *total > b.3).unwrap_or(true) {
+                    best = Some((x as i64 + 1, y as i64 + 1, i as i64, *total));
+                }
+            }
+        }
+    }
+
+    best
+}
@@ -0,0 +1,336 @@
+use crate::*;
+
+pub struct Day12;
+
+/// The line with the initial state of the pots.
+const STATE: &str = "initial state: {}";
+/// A line with a pattern of pots, and whether it produces a plant.
+const RULE: &str = "{} => {}";
+
+/// The initial state, and the patterns that produce the next generation.
+pub type Plants = (Vec<bool>, HashMap<Vec<bool>, bool>);
+
+impl Solution for Day12 {
+    type Input = Plants;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let (n, first) = lines
+            .next()
+            .ok_or_else(|| format_err!("expected initial state"))?;
+
+        let (state,) = scan!(line = n + 1, first, STATE, String)?;
+        let state = state.chars().map(|c| c == '#').collect::<Vec<_>>();
+
+        let mut m = HashMap::<Vec<bool>, bool>::new();
+
+        for (n, line) in lines {
+            let (from, to) = scan!(line = n + 1, line, RULE, String, String)?;
+
+            let to = match to.as_str() {
+                "." => false,
+                "#" => true,
+                other => bail!("bad translation: {}", other),
+            };
+
+            m.insert(from.chars().map(|c| c == '#').collect(), to);
+        }
+
+        Ok((state, m))
+    }
+
+    fn validate(input: &str) -> Vec<ParseError> {
+        let mut errors = Vec::new();
+        let mut lines = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        match lines.next() {
+            Some((n, line)) => match scan_captures(STATE, 1, n + 1, line) {
+                Ok(captures) => {
+                    for (span, state) in captures {
+                        errors.extend(pots(n + 1, line, span.start, state));
+                    }
+                }
+                Err(e) => errors.push(ParseError::from_error(n + 1, line, e)),
+            },
+            None => errors.push(ParseError::new(1, "", 0..0, "expected initial state")),
+        }
+
+        for (n, line) in lines {
+            let captures = match scan_captures(RULE, 2, n + 1, line) {
+                Ok(captures) => captures,
+                Err(e) => {
+                    errors.push(ParseError::from_error(n + 1, line, e));
+                    continue;
+                }
+            };
+
+            let (from, to) = (&captures[0], &captures[1]);
+            errors.extend(pots(n + 1, line, from.0.start, from.1));
+            errors.extend(pots(n + 1, line, to.0.start, to.1));
+
+            if from.1.len() != 5 {
+                let message = format!("expected a pattern of 5 pots, but got {}", from.1.len());
+                errors.push(ParseError::new(n + 1, line, from.0.clone(), message));
+            }
+
+            if to.1.len() != 1 {
+                let message = format!("expected a single pot, but got {}", to.1.len());
+                errors.push(ParseError::new(n + 1, line, to.0.clone(), message));
+            }
+
+            // NB: plants would grow forever in both directions from every empty pot.
+            if (from.1, to.1) == (".....", "#") {
+                let message = "empty pots can't grow plants";
+                errors.push(ParseError::new(n + 1, line, 0..line.len(), message));
+            }
+        }
+
+        return errors;
+
+        /// Report every character which isn't a pot in `text`, which starts at `offset` in `line`.
+        fn pots<'a>(
+            n: usize,
+            line: &'a str,
+            offset: usize,
+            text: &'a str,
+        ) -> impl Iterator<Item = ParseError> + 'a {
+            text.char_indices()
+                .filter(|&(_, c)| c != '#' && c != '.')
+                .map(move |(i, c)| {
+                    let span = offset + i..offset + i + c.len_utf8();
+                    let message = format!("expected `#` or `.`, but got `{}`", c);
+                    ParseError::new(n, line, span, message)
+                })
+        }
+    }
+
+    fn part1((state, m): &Self::Input) -> Result<i64, Error> {
+        calculate(state, m, 20)
+    }
+
+    fn part2((state, m): &Self::Input) -> Result<i64, Error> {
+        calculate(state, m, 50_000_000_000)
+    }
+
+    fn variants() -> Vec<Variant<Self::Input>> {
+        // NB: `better` can't extrapolate, so it's too slow for part 2.
+        vec![Variant::new(Part::One, "better", |(state, m): &Plants| {
+            Ok(better(state, m, 20))
+        })]
+    }
+}
+
+/// Better implementation that uses sparse sets to store and query the patterns.
+///
+/// Has not been implemented to detect linearly shifting plants yet.
+pub fn better(state: &[bool], m: &HashMap<Vec<bool>, bool>, generations: usize) -> i64 {
+    let patterns = m
+        .iter()
+        .filter(|e| *e.1)
+        .map(|e| {
+            (-2i64..)
+                .zip(e.0.iter().cloned())
+                .filter(|e| e.1)
+                .map(|e| e.0)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut state = (0i64..)
+        .zip(state.iter().cloned())
+        .filter(|e| e.1)
+        .map(|e| e.0)
+        .collect::<HashSet<_>>();
+
+    for _ in 0..generations {
+        // NB: once all plants have died, no more can grow.
+        let (min, max) = match state.iter().cloned().minmax().into_option() {
+            Some(range) => range,
+            None => break,
+        };
+
+        state = (min - 3..=max + 3)
+            .filter(|idx| {
+                patterns.iter().any(|pat| {
+                    [-2, -1, 0, 1, 2].iter().all(|t| {
+                        if pat.contains(t) {
+                            state.contains(&(*idx + *t))
+                        } else {
+                            !state.contains(&(*idx + *t))
+                        }
+                    })
+                })
+            })
+            .collect::<HashSet<_>>();
+    }
+
+    state.into_iter().sum()
+}
+
+pub fn calculate(
+    state: &[bool],
+    m: &HashMap<Vec<bool>, bool>,
+    generations: usize,
+) -> Result<i64, Error> {
+    let initial = (state.iter().cloned().collect::<VecDeque<_>>(), 0i64);
+
+    // NB: plants eventually settle into a pattern which shifts by the same amount every
+    // generation, so key by the pattern and let the index drift.
+    let seq = cycle::find_drifting(
+        initial,
+        |(state, index)| Ok(step(state, *index, m)),
+        |(state, index)| (state.clone(), *index),
+        generations,
+    )?;
+
+    let ((state, index), drift) = seq
+        .get(generations)
+        .ok_or_else(|| format_err!("no state for generation {}", generations))?;
+
+    Ok(sum(state, index + drift))
+}
+
+/// Calculate the next generation of plants, where the first plant in the state is at `index`.
+fn step(
+    state: &VecDeque<bool>,
+    mut index: i64,
+    m: &HashMap<Vec<bool>, bool>,
+) -> (VecDeque<bool>, i64) {
+    use std::iter;
+
+    let mut state = state.clone();
+
+    if let Some(m) = state.iter().take(3).position(|c| *c) {
+        index -= (3 - m) as i64;
+
+        for _ in 0..3 - m {
+            state.push_front(false);
+        }
+    }
+
+    if let Some(m) = state.iter().rev().take(3).position(|c| *c) {
+        for _ in 0..3 - m {
+            state.push_back(false);
+        }
+    }
+
+    let mut next = VecDeque::new();
+
+    for i in 0..state.len() {
+        let mut palette = Vec::with_capacity(5);
+
+        if i < 2 {
+            palette.extend(iter::repeat_n(false, 2 - i));
+        }
+
+        for si in i.saturating_sub(2)..usize::min(i + 3, state.len()) {
+            palette.extend(state.get(si));
+        }
+
+        if i + 3 >= state.len() {
+            palette.extend(iter::repeat_n(false, 3 - (state.len() - i)));
+        }
+
+        if let Some(m) = m.get(&palette).cloned() {
+            next.push_back(m);
+        } else {
+            next.push_back(false);
+        }
+    }
+
+    // Reduce the state as much as possible.
+    while let Some(false) = next.front().cloned() {
+        index += 1;
+        next.pop_front();
+    }
+
+    while let Some(false) = next.back().cloned() {
+        next.pop_back();
+    }
+
+    (next, index)
+}
+
+/// Sum the indexes of all pots with plants in them.
+fn sum(state: &VecDeque<bool>, index: i64) -> i64 {
+    state
+        .iter()
+        .cloned()
+        .zip(index..)
+        .filter(|(c, _)| *c)
+        .map(|(_, i)| i)
+        .sum::<i64>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_better_and_calculate_agree() {
+        gen::check(
+            200,
+            |rng| {
+                let state = (0..rng.below(30) + 1)
+                    .map(|_| rng.chance(0.5))
+                    .collect::<Vec<_>>();
+
+                // NB: empty pots must stay empty, otherwise plants grow forever in both directions.
+                let patterns = (1..32)
+                    .map(|n| ((0..5).map(|i| n & (1 << i) != 0).collect(), rng.chance(0.5)))
+                    .collect::<HashMap<Vec<bool>, bool>>();
+
+                (state, patterns, rng.below(60))
+            },
+            |(state, patterns, generations)| {
+                let a = better(state, patterns, *generations);
+                let b = calculate(state, patterns, *generations)?;
+
+                if a != b {
+                    bail!("better gave {}, but calculate {}", a, b);
+                }
+
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = "\
+initial state: #..#x#
+
+...## => #
+.#.# => #
+..#.. => x
+..... => #
+";
+
+        let errors = Day12::validate(input)
+            .into_iter()
+            .map(|e| (e.line(), e.columns(), e.message().to_string()))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            (1, 20..21, String::from("expected `#` or `.`, but got `x`")),
+            (
+                4,
+                1..5,
+                String::from("expected a pattern of 5 pots, but got 4"),
+            ),
+            (5, 10..11, String::from("expected `#` or `.`, but got `x`")),
+            (6, 1..11, String::from("empty pots can't grow plants")),
+        ];
+
+        assert_eq!(errors, expected);
+    }
+}
@@ -0,0 +1,483 @@
+use crate::events::{self, Events, Value};
+use crate::gen::Rng;
+use crate::*;
+
+pub struct Day13;
+
+/// The tracks, and the carts on them.
+pub type Tracks = (Grid<Option<Area>>, Vec<Cart>);
+
+impl Solution for Day13 {
+    type Input = Tracks;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut carts = Vec::new();
+
+        let grid = Grid::parse_padded(input, None, |(x, y), c| {
+            let pos = Pos::new(x as i64, y as i64);
+
+            let dir = match c {
+                '+' => return Ok(Some(Area::Inter)),
+                '/' => return Ok(Some(Area::Slash)),
+                '\\' => return Ok(Some(Area::BackSlash)),
+                '-' | '|' => return Ok(Some(Area::Track)),
+                ' ' => return Ok(None),
+                '>' => Dir::East,
+                '^' => Dir::North,
+                '<' => Dir::West,
+                'v' => Dir::South,
+                o => bail!("unsupported: {}", o),
+            };
+
+            carts.push((pos, Turn::Left, dir));
+            Ok(Some(Area::Track))
+        })?;
+
+        Ok((grid, carts))
+    }
+
+    fn validate(input: &str) -> Vec<ParseError> {
+        let mut errors = Vec::new();
+
+        let rows = input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let at = |x: i64, y: i64| {
+            if x < 0 || y < 0 {
+                return ' ';
+            }
+
+            rows.get(y as usize)
+                .and_then(|row| row.get(x as usize))
+                .cloned()
+                .unwrap_or(' ')
+        };
+
+        let mut carts = 0;
+
+        let lines = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty());
+
+        for (y, (n, line)) in lines.enumerate() {
+            let n = n + 1;
+
+            for (x, (i, c)) in line.char_indices().enumerate() {
+                let (x, y) = (x as i64, y as i64);
+                let span = i..i + c.len_utf8();
+
+                // NB: straight tracks and carts must lead on to track which connects back to them
+                // in both directions, otherwise carts run off the tracks.
+                let (horizontal, vertical) = match c {
+                    '-' | '<' | '>' => (true, false),
+                    '|' | '^' | 'v' => (false, true),
+                    '+' => (true, true),
+                    '/' | '\\' | ' ' => (false, false),
+                    other => {
+                        let message = format!("unsupported: {}", other);
+                        errors.push(ParseError::new(n, line, span, message));
+                        continue;
+                    }
+                };
+
+                if let '<' | '>' | '^' | 'v' = c {
+                    carts += 1;
+                }
+
+                let leads_off = horizontal
+                    && [at(x - 1, y), at(x + 1, y)]
+                        .iter()
+                        .any(|c| matches!(c, ' ' | '|' | '^' | 'v'))
+                    || vertical
+                        && [at(x, y - 1), at(x, y + 1)]
+                            .iter()
+                            .any(|c| matches!(c, ' ' | '-' | '<' | '>'));
+
+                if leads_off {
+                    errors.push(ParseError::new(n, line, span, "track leads off the tracks"));
+                }
+            }
+        }
+
+        if carts == 0 {
+            errors.push(ParseError::locate(input, 0..0, "no carts on the tracks"));
+        }
+
+        errors
+    }
+
+    fn part1((grid, carts): &Self::Input) -> Result<String, Error> {
+        Ok(solve(true, grid, carts.clone(), &mut Events::noop())?.to_string())
+    }
+
+    fn part2((grid, carts): &Self::Input) -> Result<String, Error> {
+        Ok(solve(false, grid, carts.clone(), &mut Events::noop())?.to_string())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Area {
+    Track,
+    Inter,
+    Slash,
+    BackSlash,
+}
+
+impl Area {
+    /// The direction a cart heading in `dir` leaves this area in.
+    fn steer(self, dir: Dir, turn: &mut Turn) -> Dir {
+        match (dir, self) {
+            (dir, Area::Track) => dir,
+            (dir, Area::Inter) => turn.apply(dir),
+            (Dir::West, Area::Slash) => Dir::South,
+            (Dir::West, Area::BackSlash) => Dir::North,
+            (Dir::East, Area::Slash) => Dir::North,
+            (Dir::East, Area::BackSlash) => Dir::South,
+            (Dir::North, Area::Slash) => Dir::East,
+            (Dir::North, Area::BackSlash) => Dir::West,
+            (Dir::South, Area::Slash) => Dir::West,
+            (Dir::South, Area::BackSlash) => Dir::East,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+    Left,
+    Straight,
+    Right,
+}
+
+pub type Cart = (Pos, Turn, Dir);
+
+impl Turn {
+    fn apply(&mut self, cart: Dir) -> Dir {
+        let out = match *self {
+            Turn::Left => cart.turn_left(),
+            Turn::Straight => cart,
+            Turn::Right => cart.turn_right(),
+        };
+
+        *self = match *self {
+            Turn::Left => Turn::Straight,
+            Turn::Straight => Turn::Right,
+            Turn::Right => Turn::Left,
+        };
+
+        out
+    }
+}
+
+/// Events emitted while simulating the carts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A cart moved one step.
+    Move { cart: usize, from: Pos, to: Pos },
+    /// Carts crashed at the given position.
+    Crash { pos: Pos },
+    /// Only one cart is left.
+    Last { cart: usize, pos: Pos },
+}
+
+impl events::Event for Event {
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Move { .. } => "move",
+            Event::Crash { .. } => "crash",
+            Event::Last { .. } => "last",
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, Value)> {
+        match *self {
+            Event::Move { cart, from, to } => vec![
+                ("cart", cart.into()),
+                ("from", from.into()),
+                ("to", to.into()),
+            ],
+            Event::Crash { pos } => vec![("pos", pos.into())],
+            Event::Last { cart, pos } => vec![("cart", cart.into()), ("pos", pos.into())],
+        }
+    }
+}
+
+/// Run the carts until the first crash, or until only one cart is left if `first` is `false`.
+///
+/// Carts are identified in events by their index in the given carts.
+pub fn solve(
+    first: bool,
+    grid: &Grid<Option<Area>>,
+    carts: Vec<Cart>,
+    events: &mut Events<Event>,
+) -> Result<Pos, Error> {
+    if carts.is_empty() {
+        bail!("no carts on the tracks");
+    }
+
+    let mut carts = carts.into_iter().enumerate().collect::<Vec<_>>();
+
+    for tick in 0u64.. {
+        if carts.len() == 1 {
+            let (cart, (pos, _, _)) = carts.into_iter().next().unwrap();
+            events.emit(tick, Event::Last { cart, pos })?;
+            return Ok(pos);
+        }
+
+        let mut positions = HashSet::new();
+        let mut remove = HashSet::new();
+
+        carts.sort_by_key(|(_, c)| c.0);
+
+        for (_, (pos, _, _)) in &mut carts {
+            if !positions.insert(*pos) {
+                events.emit(tick, Event::Crash { pos: *pos })?;
+
+                if first {
+                    return Ok(*pos);
+                }
+
+                remove.insert(*pos);
+            }
+        }
+
+        // no crashes, run simulation.
+
+        for (cart, (ref mut pos, ref mut turn, ref mut dir)) in carts.iter_mut() {
+            if remove.contains(pos) {
+                continue;
+            }
+
+            positions.remove(pos);
+            let from = *pos;
+
+            *pos = pos.step(*dir);
+
+            events.emit(
+                tick,
+                Event::Move {
+                    cart: *cart,
+                    from,
+                    to: *pos,
+                },
+            )?;
+
+            if !positions.insert(*pos) {
+                events.emit(tick, Event::Crash { pos: *pos })?;
+
+                if first {
+                    return Ok(*pos);
+                }
+
+                remove.insert(*pos);
+                continue;
+            }
+
+            let g = match grid.get_pos(*pos).cloned().flatten() {
+                Some(g) => g,
+                None => bail!("nothing on grid: {:?}", pos),
+            };
+
+            *dir = g.steer(*dir, turn);
+        }
+
+        if !remove.is_empty() {
+            carts.retain(|(_, c)| !remove.contains(&c.0));
+        }
+    }
+
+    bail!("could not find a result");
+}
+
+/// Generate about `size` carts on separate rectangular loops of track.
+///
+/// Every loop has a pair of carts going in opposite directions which are bound to crash, except
+/// for the first one which has an extra cart that is left at the end.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let loops = size / 2 + 1;
+    let columns = (loops as f64).sqrt().ceil() as usize;
+    let rows = loops.div_ceil(columns);
+
+    let mut grid = vec![vec![' '; columns * 10]; rows * 8];
+
+    for n in 0..loops {
+        let (ox, oy) = ((n % columns) * 10 + 1, (n / columns) * 8 + 1);
+        let (w, h) = (rng.below(6) + 3, rng.below(4) + 3);
+
+        // NB: tracks along the loop, clockwise from the top-left corner. Corners are skipped since
+        // carts can't start on them.
+        let mut tracks = Vec::new();
+
+        for x in 0..w {
+            grid[oy][ox + x] = '-';
+            grid[oy + h - 1][ox + x] = '-';
+        }
+
+        for y in 0..h {
+            grid[oy + y][ox] = '|';
+            grid[oy + y][ox + w - 1] = '|';
+        }
+
+        grid[oy][ox] = '/';
+        grid[oy][ox + w - 1] = '\\';
+        grid[oy + h - 1][ox] = '\\';
+        grid[oy + h - 1][ox + w - 1] = '/';
+
+        tracks.extend((1..w - 1).map(|x| (ox + x, oy, ['>', '<'])));
+        tracks.extend((1..h - 1).map(|y| (ox + w - 1, oy + y, ['v', '^'])));
+        tracks.extend((1..w - 1).map(|x| (ox + x, oy + h - 1, ['<', '>'])));
+        tracks.extend((1..h - 1).map(|y| (ox, oy + y, ['^', 'v'])));
+        rng.shuffle(&mut tracks);
+
+        let carts = if n == 0 { 3 } else { 2 };
+
+        for (i, (x, y, dirs)) in tracks.into_iter().take(carts).enumerate() {
+            grid[y][x] = dirs[i % 2];
+        }
+    }
+
+    let mut out = String::new();
+
+    for row in grid {
+        out.extend(row);
+        out.push('\n');
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Log;
+
+    #[test]
+    fn test_events() -> Result<(), Error> {
+        let (grid, carts) =
+            Day13::parse("/>-<\\  \n|   |  \n| /<+-\\\n| | | v\n\\>+</ |\n  |   ^\n  \\<->/\n")?;
+
+        let log = Log::default();
+        assert_eq!(
+            solve(false, &grid, carts, &mut Events::log(&log))?,
+            Pos::new(6, 4)
+        );
+
+        let events = log.events();
+        let crashes = events
+            .iter()
+            .filter(|(_, e)| matches!(e, Event::Crash { .. }))
+            .count();
+
+        assert_eq!(crashes, 4);
+        assert_eq!(
+            events.last(),
+            Some(&(
+                3,
+                Event::Last {
+                    cart: 8,
+                    pos: Pos::new(6, 4)
+                }
+            ))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_ragged() -> Result<(), Error> {
+        let input = "/>-<\\  \n|   |  \n| /<+-\\\n| | | v\n\\>+</ |\n  |   ^\n  \\<->/\n";
+
+        // NB: editors commonly strip the trailing spaces from the first two lines.
+        let trimmed = input
+            .lines()
+            .map(|line| format!("{}\n", line.trim_end()))
+            .collect::<String>();
+
+        let tracks = Day13::parse(input)?;
+        let ragged = Day13::parse(&trimmed)?;
+
+        assert_eq!((ragged.0.width(), ragged.0.height()), (7, 7));
+        assert_eq!(Day13::part2(&ragged)?, Day13::part2(&tracks)?);
+        assert_eq!(Day13::part2(&ragged)?, "6,4");
+        Ok(())
+    }
+
+    #[test]
+    fn test_steer() {
+        use self::Dir::*;
+
+        let curves = [
+            (
+                Area::Slash,
+                [(North, East), (East, North), (South, West), (West, South)],
+            ),
+            (
+                Area::BackSlash,
+                [(North, West), (East, South), (South, East), (West, North)],
+            ),
+            (
+                Area::Track,
+                [(North, North), (East, East), (South, South), (West, West)],
+            ),
+        ];
+
+        for (area, cases) in curves.iter() {
+            for &(dir, expected) in cases {
+                let mut turn = Turn::Left;
+                assert_eq!(area.steer(dir, &mut turn), expected, "{:?} {:?}", area, dir);
+                assert_eq!(turn, Turn::Left, "{:?} {:?}", area, dir);
+            }
+        }
+
+        // intersections turn left, go straight and then turn right, in that order.
+        let intersections = [
+            (North, [West, North, East]),
+            (East, [North, East, South]),
+            (South, [East, South, West]),
+            (West, [South, West, North]),
+        ];
+
+        for &(dir, expected) in intersections.iter() {
+            let mut turn = Turn::Left;
+
+            for &expected in expected.iter() {
+                assert_eq!(Area::Inter.steer(dir, &mut turn), expected, "{:?}", dir);
+            }
+
+            assert_eq!(turn, Turn::Left);
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let errors = |input: &str| {
+            Day13::validate(input)
+                .into_iter()
+                .map(|e| (e.line(), e.columns(), e.message().to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(errors("/->-\\\n|   |\n\\---/\n"), vec![]);
+
+        assert_eq!(
+            errors("/->-\\\n|   |  -\n\\-x-/\n"),
+            vec![
+                (2, 8..9, String::from("track leads off the tracks")),
+                (3, 3..4, String::from("unsupported: x")),
+            ]
+        );
+
+        assert_eq!(
+            errors("/---\\\n|   |\n\\---/\n"),
+            vec![(1, 1..1, String::from("no carts on the tracks"))]
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use crate::*;
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    /// The puzzle input, which is both a number of recipes and a sequence of scores.
+    type Input = usize;
+    type Part1 = String;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(str::parse(input.trim())?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<String, Error> {
+        Ok(part1(*input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<usize, Error> {
+        Ok(part2(*input))
+    }
+}
+
+struct Recipe {
+    pub data: Vec<usize>,
+    a: usize,
+    b: usize,
+}
+
+impl Recipe {
+    fn new() -> Self {
+        let data = vec![3, 7];
+        Recipe { data, a: 0, b: 1 }
+    }
+
+    fn make(&mut self) -> usize {
+        let a = self.a;
+        let b = self.b;
+
+        let mut sum = self.data[a] + self.data[b];
+
+        let mut c = 1;
+
+        while sum >= 10 {
+            c += 1;
+            self.data.push(sum % 10);
+            sum /= 10;
+        }
+
+        self.data.push(sum);
+
+        let s = self.data.len() - c;
+        self.data[s..].reverse();
+
+        self.a = (a + self.data[a] + 1) % self.data.len();
+        self.b = (b + self.data[b] + 1) % self.data.len();
+        self.data.len()
+    }
+}
+
+pub fn part1(input: usize) -> String {
+    let mut recipe = Recipe::new();
+
+    while recipe.make() < (input + 10) {}
+
+    recipe.data[input..(input + 10)]
+        .iter()
+        .cloned()
+        .map(|d| d.to_string())
+        .collect()
+}
+
+pub fn part2(mut input: usize) -> usize {
+    let mut recipe = Recipe::new();
+
+    let needle = {
+        let mut needle = Vec::new();
+
+        while input > 9 {
+            needle.push(input % 10);
+            input /= 10;
+        }
+
+        needle.push(input);
+        needle.reverse();
+        needle
+    };
+
+    let mut ptr = 0;
+
+    loop {
+        let cur = recipe.make();
+
+        while ptr + needle.len() < cur {
+            if needle == recipe.data[ptr..(ptr + needle.len())] {
+                return ptr;
+            }
+
+            ptr += 1;
+        }
+    }
+}
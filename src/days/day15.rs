@@ -0,0 +1,1628 @@
+use crate::events::{self, Events, Value};
+use crate::export::Palette;
+use crate::gen::Rng;
+use crate::visuals::{Frame, Visuals};
+use crate::*;
+use rayon::prelude::*;
+
+use std::fmt;
+use std::ops;
+
+pub type UnitId = usize;
+
+/// A line in the header setting the stats of a faction.
+const FACTION: &str = "{}: {} hit points, {} attack power";
+/// A line in the header setting the stats of a faction whose units have armour.
+const ARMOURED: &str = "{}: {} hit points, {} attack power, {} armour";
+/// A line in the header making two factions allies.
+const ALLIANCE: &str = "{} and {} are allies";
+/// The hit points of a unit, annotated to the right of its row in the map.
+const ANNOTATION: &str = "{}({})";
+/// Separates a row of the map from the annotations of its units.
+const SEPARATOR: &str = "   ";
+/// Separates the annotations of units on the same row.
+const DELIMITER: &str = ", ";
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = State;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        State::load(input)
+    }
+
+    fn part1(state: &Self::Input) -> Result<u64, Error> {
+        Ok(state.clone().simulate()?.score())
+    }
+
+    fn part2(state: &Self::Input) -> Result<u64, Error> {
+        let (_, outcome) =
+            save_the_elves(state.clone(), &mut Visuals::noop(), &mut Events::noop())?;
+        Ok(outcome.score())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    position_by_unit: HashMap<UnitId, Pos>,
+    unit_by_position: HashMap<Pos, UnitId>,
+    walls: Grid<bool>,
+    units: HashMap<UnitId, Unit>,
+    factions: Factions,
+    title: Option<String>,
+    killed: BTreeMap<Kind, u64>,
+}
+
+impl State {
+    /// Load state from the given string, where goblins fight elves unless the map is preceded by
+    /// a header saying otherwise.
+    ///
+    /// Rows of the map can be annotated with the hit points of their units, like the puzzle does
+    /// with `#G.E#   G(200), E(131)`. Units in rows which aren't annotated have full hit points.
+    ///
+    /// See [`Factions`] and [`State::annotated`].
+    pub fn load(input: &str) -> Result<State, Error> {
+        State::load_with(input, Factions::default())
+    }
+
+    /// Load state from the given string with the given factions, which the header of the map can
+    /// change further.
+    pub fn load_with(input: &str, mut factions: Factions) -> Result<State, Error> {
+        let map = factions.parse_header(input)?;
+        let (map, rows) = parse_annotations(&map)?;
+        let mut units = Vec::new();
+
+        let walls = Grid::parse(&map, |(x, y), c| {
+            let kind = match c {
+                '#' => return Ok(true),
+                '.' => return Ok(false),
+                c => Kind(c),
+            };
+
+            let stats = match factions.stats(kind) {
+                Some(stats) => stats,
+                None => bail!("unsupported: {}", c),
+            };
+
+            units.push((Pos::new(x as i64, y as i64), Unit::new(kind, stats)));
+            Ok(false)
+        })?;
+
+        for (y, row) in rows.iter().enumerate() {
+            if row.annotations.is_empty() {
+                continue;
+            }
+
+            let mut units = units
+                .iter_mut()
+                .filter(|(pos, _)| pos.y == y as i64)
+                .map(|(_, unit)| unit);
+
+            for a in &row.annotations {
+                let unit = match units.next() {
+                    Some(unit) => unit,
+                    None => {
+                        let message = "annotation without a unit";
+                        return Err(
+                            ParseError::new(row.line, row.text, a.span.clone(), message).into()
+                        );
+                    }
+                };
+
+                if unit.kind != a.kind {
+                    let message = format!("expected annotation for `{}`", unit.kind);
+                    return Err(ParseError::new(row.line, row.text, a.span.clone(), message).into());
+                }
+
+                unit.hit_points = a.hit_points;
+            }
+
+            if let Some(unit) = units.next() {
+                let end = row.text.len();
+                let message = format!("missing annotation for `{}`", unit.kind);
+                return Err(ParseError::new(row.line, row.text, end..end, message).into());
+            }
+        }
+
+        let mut state = State {
+            walls,
+            factions,
+            ..Self::default()
+        };
+
+        for (pos, unit) in units {
+            let id = state.units.len();
+            state.units.insert(id, unit);
+            state.position_by_unit.insert(id, pos);
+            state.unit_by_position.insert(pos, id);
+        }
+
+        Ok(state)
+    }
+
+    /// Remove a unit by ID.
+    pub fn remove_unit(&mut self, id: UnitId) -> Result<(), Error> {
+        if self.units.remove(&id).is_none() {
+            bail!("no such unit: {}", id);
+        }
+
+        let pos = match self.position_by_unit.remove(&id) {
+            Some(pos) => pos,
+            None => bail!("no position for unit: {}", id),
+        };
+
+        let old_id = match self.unit_by_position.remove(&pos) {
+            Some(id) => id,
+            None => bail!("no unit for position: {:?}", pos),
+        };
+
+        if old_id != id {
+            bail!("wrong unit for position: {:?}", pos);
+        }
+
+        Ok(())
+    }
+
+    /// Moves a unit from one position to another.
+    pub fn move_unit(&mut self, from: Pos, to: Pos) -> Result<(), Error> {
+        let id = match self.unit_by_position.remove(&from) {
+            Some(id) => id,
+            None => bail!("no unit at position: {:?}", from),
+        };
+
+        if let Some(other_id) = self.unit_by_position.insert(to, id) {
+            bail!(
+                "there was already a unit at position {:?}: {}",
+                to,
+                other_id
+            );
+        }
+
+        if let Some(old_pos) = self.position_by_unit.insert(id, to) {
+            if old_pos != from {
+                bail!(
+                    "wrong position `{:?}` recorded for unit `{}`, expected: {:?}",
+                    old_pos,
+                    id,
+                    from
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find the position of a unit.
+    fn find_unit_position(&self, id: UnitId) -> Result<Pos, Error> {
+        match self.position_by_unit.get(&id) {
+            Some(pos) => Ok(*pos),
+            None => bail!("no position for unit: {}", id),
+        }
+    }
+
+    /// Find all units to perform actions based on their order.
+    pub fn find_priority_units(&self) -> Vec<UnitId> {
+        let mut units = self
+            .position_by_unit
+            .iter()
+            .map(|(id, p)| (*id, *p))
+            .collect::<Vec<_>>();
+
+        units.sort_by_key(|(_, p)| *p);
+        units.into_iter().map(|(id, _)| id).collect::<Vec<_>>()
+    }
+
+    /// Find the given unit by its position.
+    pub fn find_unit_by_position(&self, pos: Pos) -> Option<&Unit> {
+        let id = self.unit_by_position.get(&pos).cloned()?;
+
+        self.units.get(&id)
+    }
+
+    /// Get unit data.
+    pub fn find_unit(&self, id: UnitId) -> Result<&Unit, Error> {
+        match self.units.get(&id) {
+            None => bail!("no unit by id: {}", id),
+            Some(unit) => Ok(unit),
+        }
+    }
+
+    pub fn find_mut_unit(&mut self, id: UnitId) -> Result<&mut Unit, Error> {
+        match self.units.get_mut(&id) {
+            None => bail!("no unit by id: {}", id),
+            Some(unit) => Ok(unit),
+        }
+    }
+
+    /// Find the first step towards the nearest target, or `None` if no target is reachable.
+    ///
+    /// The square to move to is the nearest free square next to a target, with ties broken in
+    /// reading order. Both the square and the first step are found in a single breadth-first search
+    /// on a dense grid, where each square carries the first step taken to reach it. Since the first
+    /// steps are queued in reading order and every layer is expanded in the order it was queued,
+    /// each square carries the first step that comes first in reading order among its shortest
+    /// paths.
+    fn find_next_step(
+        &self,
+        scratch: &mut Scratch,
+        from: Pos,
+        targets: impl IntoIterator<Item = Pos>,
+    ) -> Option<Pos> {
+        let pos = |(x, y): Coord| Pos::new(x as i64, y as i64);
+        let from = from.to_coord()?;
+
+        scratch.next_search();
+
+        let Scratch {
+            search,
+            visited,
+            in_range,
+            queue,
+        } = scratch;
+
+        let search = *search;
+
+        // NB: occupied squares are marked as visited, so that they're never queued.
+        for c in self.position_by_unit.values().filter_map(|p| p.to_coord()) {
+            visited[c] = search;
+        }
+
+        for target in targets.into_iter().filter_map(Pos::to_coord) {
+            for c in self.walls.neighbours4(target) {
+                in_range[c] = search;
+            }
+        }
+
+        for c in self.walls.neighbours4(from) {
+            if visited[c] < search {
+                visited[c] = search;
+                queue.push_back((c, c, 1));
+            }
+        }
+
+        let mut best = None::<(usize, Coord, Coord)>;
+
+        while let Some((c, step, distance)) = queue.pop_front() {
+            if let Some((nearest, _, _)) = best {
+                if distance > nearest {
+                    break;
+                }
+            }
+
+            if in_range[c] == search {
+                // NB: squares within a layer aren't queued in reading order.
+                if best
+                    .map(|(_, square, _)| pos(c) < pos(square))
+                    .unwrap_or(true)
+                {
+                    best = Some((distance, c, step));
+                }
+
+                continue;
+            }
+
+            // NB: internal iteration optimizes better than a `for` loop over the neighbours.
+            self.walls.neighbours4(c).for_each(|n| {
+                if visited[n] < search {
+                    visited[n] = search;
+                    queue.push_back((n, step, distance + 1));
+                }
+            });
+        }
+
+        let (_, _, step) = best?;
+        Some(pos(step))
+    }
+
+    /// Find targets to attack.
+    fn find_attack_target(&self, my_id: UnitId) -> Result<Option<UnitId>, Error> {
+        let me = self.find_unit(my_id)?;
+        let my_pos = self.find_unit_position(my_id)?;
+
+        let mut attack = None;
+
+        for target_id in self.find_priority_units() {
+            let target_unit = self.find_unit(target_id)?;
+
+            if !self.is_target(me, target_unit) {
+                continue;
+            }
+
+            let other_pos = self.find_unit_position(target_id)?;
+
+            if my_pos.manhattan(other_pos) == 1 {
+                attack = match attack {
+                    Some((_, hit_points)) if hit_points > target_unit.hit_points => {
+                        Some((target_id, target_unit.hit_points))
+                    }
+                    None => Some((target_id, target_unit.hit_points)),
+                    other => other,
+                };
+            }
+        }
+
+        Ok(attack.map(|(id, _)| id))
+    }
+
+    /// Test if the given position has a unit.
+    pub fn is_unit_at(&self, pos: Pos) -> bool {
+        self.unit_by_position.contains_key(&pos)
+    }
+
+    /// Test if the given id is a unit.
+    pub fn is_unit(&self, id: UnitId) -> bool {
+        self.units.contains_key(&id)
+    }
+
+    /// Test if the other unit is a valid target for the given unit.
+    pub fn is_target(&self, unit: &Unit, other: &Unit) -> bool {
+        self.factions.is_enemy(unit.kind, other.kind)
+    }
+
+    /// Test if any units left are enemies of each other.
+    fn is_fighting(&self) -> bool {
+        self.units
+            .values()
+            .any(|a| self.units.values().any(|b| self.is_target(a, b)))
+    }
+
+    /// A copy of the state where all elves have the given attack power.
+    pub fn with_elf_attack_power(&self, ap: u64) -> State {
+        let mut state = self.clone();
+        state.title = Some(format!("Attack Power: {}", ap));
+
+        if let Some(stats) = state.factions.stats.get_mut(&Kind::ELF) {
+            stats.attack_power = ap;
+        }
+
+        for u in state.units.values_mut() {
+            if u.kind == Kind::ELF {
+                u.attack_power = ap;
+            }
+        }
+
+        state
+    }
+
+    /// The state in the text format of the puzzle, which can be loaded again with [`State::load`].
+    ///
+    /// Unit IDs are not preserved, since they are assigned in reading order when loading.
+    pub fn annotated(&self) -> Annotated<'_> {
+        Annotated(self)
+    }
+
+    /// Draw the state of combat before the given round.
+    fn draw(&self, f: &mut Frame, tick: u64) {
+        f.print(0, 0, Display(self));
+
+        if let Some(title) = self.title.as_ref() {
+            f.line(title);
+        }
+
+        let killed = self
+            .killed
+            .iter()
+            .map(|(kind, n)| format!("{}: {}", kind, n))
+            .join(", ");
+
+        f.line(format!("Killed: {}", killed));
+        f.line(format!("Tick: {}", tick));
+
+        for id in self.find_priority_units() {
+            if let Some(unit) = self.units.get(&id) {
+                f.line(format!("{}: {} HP", unit.kind, unit.hit_points));
+            }
+        }
+    }
+
+    /// Simulate the battle until no units left are enemies of each other, returning its outcome.
+    pub fn simulate(&mut self) -> Result<Outcome, Error> {
+        self.simulate_with(&mut Visuals::noop(), &mut Events::noop())
+    }
+
+    /// Simulate the battle like [`State::simulate`], while drawing every round and emitting events
+    /// with the round they happened in.
+    pub fn simulate_with(
+        &mut self,
+        visuals: &mut Visuals,
+        events: &mut Events<Event>,
+    ) -> Result<Outcome, Error> {
+        self.run(None, None, visuals, events)?
+            .ok_or_else(|| format_err!("battle was aborted"))
+    }
+
+    /// Simulate the battle like [`State::simulate`], but abort it with `None` as soon as a unit of
+    /// the given kind dies.
+    pub fn simulate_until_death(&mut self, kind: Kind) -> Result<Option<Outcome>, Error> {
+        self.run(Some(kind), None, &mut Visuals::noop(), &mut Events::noop())
+    }
+
+    /// Simulate at most the given number of full rounds of the battle, returning its outcome if it
+    /// ended before then.
+    ///
+    /// Simulating the battle again resumes it, but rounds are then counted from where it resumed.
+    pub fn simulate_rounds(&mut self, rounds: u64) -> Result<Option<Outcome>, Error> {
+        self.run(
+            None,
+            Some(rounds),
+            &mut Visuals::noop(),
+            &mut Events::noop(),
+        )
+    }
+
+    /// Run the battle, aborting it as soon as a unit of the `abort_on_death` kind dies or after
+    /// `max_rounds` full rounds.
+    fn run(
+        &mut self,
+        abort_on_death: Option<Kind>,
+        max_rounds: Option<u64>,
+        visuals: &mut Visuals,
+        events: &mut Events<Event>,
+    ) -> Result<Option<Outcome>, Error> {
+        let mut scratch = Scratch::new(&self.walls);
+
+        // execute turns
+        for tick in 0u64.. {
+            if max_rounds == Some(tick) {
+                return Ok(None);
+            }
+
+            visuals.draw(|f| self.draw(f, tick))?;
+
+            let prioritized_units = self.find_priority_units();
+
+            for my_id in prioritized_units.iter().cloned() {
+                if !self.is_unit(my_id) {
+                    continue;
+                }
+
+                let me = self.find_unit(my_id)?;
+
+                // combat ends when a unit finds no targets at the start of its turn, unless units
+                // it is allied with are still fighting each other.
+                if !self.units.values().any(|u| self.is_target(me, u)) {
+                    if self.is_fighting() {
+                        continue;
+                    }
+
+                    let outcome = Outcome {
+                        rounds: tick,
+                        winners: self.units.values().map(|u| u.kind).collect(),
+                        survivors: self
+                            .find_priority_units()
+                            .into_iter()
+                            .filter_map(|id| Some((id, self.units.get(&id)?.hit_points)))
+                            .collect(),
+                        deaths: self.killed.clone(),
+                    };
+
+                    visuals.draw(|f| self.draw(f, tick))?;
+                    events.emit(
+                        tick,
+                        Event::End {
+                            rounds: outcome.rounds,
+                            hit_points: outcome.hit_points(),
+                        },
+                    )?;
+                    return Ok(Some(outcome));
+                }
+
+                // units to attack.
+                let mut attack = self.find_attack_target(my_id)?;
+
+                // if there is nothing to attack, then move.
+                if attack.is_none() {
+                    // units to target.
+                    let mut targets = Vec::new();
+
+                    for target_id in prioritized_units.iter().cloned().filter(|id| my_id != *id) {
+                        if !self.is_unit(target_id) {
+                            continue;
+                        }
+
+                        let other_pos = self.find_unit_position(target_id)?;
+                        let target = self.find_unit(target_id)?;
+
+                        if !self.is_target(me, target) {
+                            continue;
+                        }
+
+                        targets.push(other_pos);
+                    }
+
+                    let my_pos = self.find_unit_position(my_id)?;
+
+                    if let Some(next_pos) = self.find_next_step(&mut scratch, my_pos, targets) {
+                        self.move_unit(my_pos, next_pos)?;
+
+                        let event = Event::Move {
+                            unit: my_id,
+                            from: my_pos,
+                            to: next_pos,
+                        };
+
+                        events.emit(tick, event)?;
+                        attack = self.find_attack_target(my_id)?;
+                    }
+                }
+
+                if let Some(enemy_id) = attack {
+                    let damage = self
+                        .find_unit(enemy_id)?
+                        .damage_from(self.find_unit(my_id)?);
+
+                    let enemy = self.find_mut_unit(enemy_id)?;
+                    enemy.hit_points = enemy.hit_points.saturating_sub(damage);
+
+                    let event = Event::Attack {
+                        unit: my_id,
+                        target: enemy_id,
+                        damage,
+                        hit_points: enemy.hit_points,
+                    };
+
+                    events.emit(tick, event)?;
+
+                    if enemy.hit_points == 0 {
+                        let killed_kind = enemy.kind;
+                        self.remove_unit(enemy_id)?;
+                        *self.killed.entry(killed_kind).or_default() += 1;
+
+                        let event = Event::Kill {
+                            unit: my_id,
+                            target: enemy_id,
+                            kind: killed_kind,
+                        };
+
+                        events.emit(tick, event)?;
+
+                        if abort_on_death == Some(killed_kind) {
+                            return Ok(None);
+                        }
+                    }
+
+                    continue;
+                }
+            }
+        }
+
+        bail!("could not find a result");
+    }
+}
+
+/// Buffers reused between searches for the next step of units, so that searching doesn't
+/// allocate.
+#[derive(Debug)]
+struct Scratch {
+    /// The current search. Squares are compared against it instead of clearing the grids.
+    search: u8,
+    /// The last search each square was visited in, where walls are always visited.
+    visited: Grid<u8>,
+    /// The last search each square was in range of a target in.
+    in_range: Grid<u8>,
+    /// Squares to visit, with the first step taken to reach them and their distance.
+    queue: VecDeque<(Coord, Coord, usize)>,
+}
+
+impl Scratch {
+    /// Marks walls in `visited`, which no search can reach.
+    const WALL: u8 = u8::MAX;
+
+    /// Construct buffers for searching the given map.
+    fn new(walls: &Grid<bool>) -> Scratch {
+        Scratch {
+            search: 0,
+            visited: walls.map(|_, wall| if *wall { Self::WALL } else { 0 }),
+            in_range: Grid::new(walls.width(), walls.height(), 0),
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Start a new search, only clearing the grids once every possible search has been used.
+    fn next_search(&mut self) {
+        if self.search == Self::WALL - 1 {
+            for (_, v) in self.visited.iter_mut().filter(|(_, v)| **v != Self::WALL) {
+                *v = 0;
+            }
+
+            for (_, v) in self.in_range.iter_mut() {
+                *v = 0;
+            }
+
+            self.search = 0;
+        }
+
+        self.search += 1;
+        self.queue.clear();
+    }
+}
+
+/// The outcome of a battle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The number of full rounds completed before combat ended.
+    pub rounds: u64,
+    /// The factions of the units left standing.
+    pub winners: BTreeSet<Kind>,
+    /// The hit points left for each unit left standing, in reading order.
+    pub survivors: Vec<(UnitId, u64)>,
+    /// The number of units killed in each faction.
+    pub deaths: BTreeMap<Kind, u64>,
+}
+
+impl Outcome {
+    /// The total hit points left across all survivors.
+    pub fn hit_points(&self) -> u64 {
+        self.survivors
+            .iter()
+            .map(|(_, hit_points)| hit_points)
+            .sum()
+    }
+
+    /// The score of the battle, the number of full rounds multiplied by the hit points left.
+    pub fn score(&self) -> u64 {
+        self.rounds * self.hit_points()
+    }
+}
+
+/// A faction, identified by the character its units are marked with on the map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Kind(char);
+
+impl Kind {
+    pub const GOBLIN: Kind = Kind('G');
+    pub const ELF: Kind = Kind('E');
+
+    /// Construct a faction marked with the given character.
+    pub fn new(symbol: char) -> Kind {
+        Kind(symbol)
+    }
+
+    /// The character units of the faction are marked with on the map.
+    pub fn symbol(self) -> char {
+        self.0
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(fmt)
+    }
+}
+
+/// The stats of every unit in a faction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub hit_points: u64,
+    pub attack_power: u64,
+    /// Reduces the damage of every attack against the unit, down to a minimum of 1.
+    pub armour: u64,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats {
+            hit_points: 200,
+            attack_power: 3,
+            armour: 0,
+        }
+    }
+}
+
+/// The factions taking part in a battle, the stats of their units, and which of them are allies.
+///
+/// By default goblins (`G`) fight elves (`E`), and all units have 200 hit points and 3 attack
+/// power. A map can be preceded by a header and an empty line, where each line of the header
+/// either sets the stats of a faction or makes two factions allies:
+///
+/// ```text
+/// E: 200 hit points, 3 attack power, 1 armour
+/// T: 300 hit points, 5 attack power
+/// E and T are allies
+///
+/// #######
+/// #E.G.T#
+/// #######
+/// ```
+#[derive(Debug, Clone)]
+pub struct Factions {
+    stats: BTreeMap<Kind, Stats>,
+    allies: HashSet<(Kind, Kind)>,
+}
+
+impl Factions {
+    /// Construct without any factions.
+    pub fn new() -> Factions {
+        Factions {
+            stats: BTreeMap::new(),
+            allies: HashSet::new(),
+        }
+    }
+
+    /// Add a faction, or change the stats of an existing one.
+    pub fn insert(&mut self, kind: Kind, stats: Stats) {
+        self.stats.insert(kind, stats);
+    }
+
+    /// Make two factions allies, so that their units don't attack each other.
+    pub fn ally(&mut self, a: Kind, b: Kind) {
+        self.allies.insert((a, b));
+        self.allies.insert((b, a));
+    }
+
+    /// The stats of units in the given faction, if it exists.
+    pub fn stats(&self, kind: Kind) -> Option<Stats> {
+        self.stats.get(&kind).cloned()
+    }
+
+    /// Test if units of the two factions attack each other.
+    pub fn is_enemy(&self, a: Kind, b: Kind) -> bool {
+        a != b && !self.allies.contains(&(a, b))
+    }
+
+    /// Apply the header of the given input, returning the map which follows it.
+    ///
+    /// The header is every line before the first empty line, as long as a map follows it.
+    fn parse_header(&mut self, input: &str) -> Result<String, Error> {
+        let lines = input.lines().collect::<Vec<_>>();
+
+        let end = match lines.iter().position(|line| line.trim().is_empty()) {
+            Some(end) if lines[end..].iter().any(|line| !line.trim().is_empty()) => end,
+            _ => return Ok(input.to_string()),
+        };
+
+        for (n, line) in lines[..end].iter().cloned().enumerate() {
+            let n = n + 1;
+
+            if line.ends_with(ending(ALLIANCE)) {
+                let (a, b) = scan!(line = n, line, ALLIANCE, char, char)?;
+
+                for kind in [Kind(a), Kind(b)] {
+                    if !self.stats.contains_key(&kind) {
+                        let message = format!("no faction `{}`", kind);
+                        return Err(ParseError::new(n, line, 0..line.len(), message).into());
+                    }
+                }
+
+                self.ally(Kind(a), Kind(b));
+                continue;
+            }
+
+            let (symbol, hit_points, attack_power, armour) = if line.ends_with(ending(ARMOURED)) {
+                scan!(line = n, line, ARMOURED, char, u64, u64, u64)?
+            } else {
+                let (symbol, hit_points, attack_power) =
+                    scan!(line = n, line, FACTION, char, u64, u64)?;
+                (symbol, hit_points, attack_power, 0)
+            };
+
+            if symbol == '#' || symbol == '.' || symbol.is_whitespace() {
+                let message = format!("`{}` can't mark a faction", symbol);
+                let span = 0..symbol.len_utf8();
+                return Err(ParseError::new(n, line, span, message).into());
+            }
+
+            let stats = Stats {
+                hit_points,
+                attack_power,
+                armour,
+            };
+
+            self.insert(Kind(symbol), stats);
+        }
+
+        // NB: the header is blanked out rather than removed, so that errors in the map point to the
+        // right line.
+        let mut map = "\n".repeat(end);
+
+        for line in &lines[end..] {
+            map.push_str(line);
+            map.push('\n');
+        }
+
+        Ok(map)
+    }
+
+    /// Write the factions and alliances which differ from the defaults as a header.
+    fn write_header(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let defaults = Factions::default();
+        let mut empty = true;
+
+        for (kind, stats) in &self.stats {
+            if defaults.stats(*kind) == Some(*stats) {
+                continue;
+            }
+
+            let line = if stats.armour > 0 {
+                fill(
+                    ARMOURED,
+                    &[kind, &stats.hit_points, &stats.attack_power, &stats.armour],
+                )
+            } else {
+                fill(FACTION, &[kind, &stats.hit_points, &stats.attack_power])
+            };
+
+            writeln!(fmt, "{}", line)?;
+            empty = false;
+        }
+
+        let mut allies = self
+            .allies
+            .iter()
+            .filter(|(a, b)| a < b)
+            .collect::<Vec<_>>();
+        allies.sort();
+
+        for (a, b) in allies {
+            writeln!(fmt, "{}", fill(ALLIANCE, &[a, b]))?;
+            empty = false;
+        }
+
+        if !empty {
+            writeln!(fmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The literal text which lines matching the given template end with.
+fn ending(template: &str) -> &str {
+    template.rsplit("{}").next().unwrap_or_default()
+}
+
+/// A row of the map, with the units annotated to the right of it.
+struct Row<'a> {
+    line: usize,
+    text: &'a str,
+    annotations: Vec<Annotation>,
+}
+
+/// The hit points of a unit, and where in its row they were annotated.
+struct Annotation {
+    span: ops::Range<usize>,
+    kind: Kind,
+    hit_points: u64,
+}
+
+/// Split the annotations off every row of the given map, returning the bare map and the
+/// annotations of each non-empty row.
+///
+/// Annotations follow the row after [`SEPARATOR`], where every row is as wide as the first one.
+fn parse_annotations(map: &str) -> Result<(String, Vec<Row<'_>>), Error> {
+    let mut bare = String::new();
+    let mut rows = Vec::new();
+    let mut width = None;
+
+    for (n, line) in map.lines().enumerate() {
+        if line.trim().is_empty() {
+            bare.push('\n');
+            continue;
+        }
+
+        // NB: leading whitespace is part of the row, so that it's reported as a bad square.
+        let width = *width.get_or_insert_with(|| {
+            let start = line.len() - line.trim_start().len();
+            let end = line[start..]
+                .find(char::is_whitespace)
+                .map_or(line.len(), |i| start + i);
+            line[..end].chars().count()
+        });
+
+        let end = line
+            .char_indices()
+            .nth(width)
+            .map(|(i, _)| i)
+            .unwrap_or(line.len());
+
+        let (row, rest) = line.split_at(end);
+        bare.push_str(row);
+        bare.push('\n');
+
+        let mut annotations = Vec::new();
+
+        if !rest.is_empty() {
+            let text = match rest.strip_prefix(SEPARATOR) {
+                Some(text) => text,
+                None => {
+                    let message = format!(
+                        "expected a row of {} columns, followed by `{}` and annotations",
+                        width, SEPARATOR
+                    );
+                    return Err(ParseError::new(n + 1, line, end..line.len(), message).into());
+                }
+            };
+
+            let mut offset = end + SEPARATOR.len();
+
+            for text in text.split(DELIMITER) {
+                let span = offset..offset + text.len();
+                offset += text.len() + DELIMITER.len();
+
+                let (symbol, hit_points) = scan!(line = n + 1, text, ANNOTATION, char, u64)
+                    .map_err(|_| {
+                        let message = format!(
+                            "expected annotation like `{}`",
+                            fill(ANNOTATION, &[&Kind::GOBLIN, &200])
+                        );
+                        ParseError::new(n + 1, line, span.clone(), message)
+                    })?;
+
+                if hit_points == 0 {
+                    let message = "units must have hit points left";
+                    return Err(ParseError::new(n + 1, line, span, message).into());
+                }
+
+                annotations.push(Annotation {
+                    span,
+                    kind: Kind(symbol),
+                    hit_points,
+                });
+            }
+        }
+
+        rows.push(Row {
+            line: n + 1,
+            text: line,
+            annotations,
+        });
+    }
+
+    Ok((bare, rows))
+}
+
+impl Default for Factions {
+    fn default() -> Self {
+        let mut factions = Factions::new();
+        factions.insert(Kind::GOBLIN, Stats::default());
+        factions.insert(Kind::ELF, Stats::default());
+        factions
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Unit {
+    kind: Kind,
+    hit_points: u64,
+    attack_power: u64,
+    armour: u64,
+}
+
+impl Unit {
+    pub fn new(kind: Kind, stats: Stats) -> Self {
+        Self {
+            kind,
+            hit_points: stats.hit_points,
+            attack_power: stats.attack_power,
+            armour: stats.armour,
+        }
+    }
+
+    /// The damage that an attack by the given unit deals to this unit.
+    pub fn damage_from(&self, attacker: &Unit) -> u64 {
+        attacker.attack_power.saturating_sub(self.armour).max(1)
+    }
+}
+
+/// Events emitted during a battle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A new battle started where elves have the given attack power.
+    Battle { elf_attack_power: u64 },
+    /// A unit moved one step.
+    Move { unit: UnitId, from: Pos, to: Pos },
+    /// A unit attacked another unit, which has the given hit points left.
+    Attack {
+        unit: UnitId,
+        target: UnitId,
+        damage: u64,
+        hit_points: u64,
+    },
+    /// A unit killed another unit of the given faction.
+    Kill {
+        unit: UnitId,
+        target: UnitId,
+        kind: Kind,
+    },
+    /// The battle ended after the given number of full rounds.
+    End { rounds: u64, hit_points: u64 },
+}
+
+impl events::Event for Event {
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Battle { .. } => "battle",
+            Event::Move { .. } => "move",
+            Event::Attack { .. } => "attack",
+            Event::Kill { .. } => "kill",
+            Event::End { .. } => "end",
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, Value)> {
+        match *self {
+            Event::Battle { elf_attack_power } => {
+                vec![("elf_attack_power", elf_attack_power.into())]
+            }
+            Event::Move { unit, from, to } => vec![
+                ("unit", unit.into()),
+                ("from", from.into()),
+                ("to", to.into()),
+            ],
+            Event::Attack {
+                unit,
+                target,
+                damage,
+                hit_points,
+            } => vec![
+                ("unit", unit.into()),
+                ("target", target.into()),
+                ("damage", damage.into()),
+                ("hit_points", hit_points.into()),
+            ],
+            Event::Kill { unit, target, kind } => vec![
+                ("unit", unit.into()),
+                ("target", target.into()),
+                ("kind", kind.symbol().into()),
+            ],
+            Event::End { rounds, hit_points } => {
+                vec![("rounds", rounds.into()), ("hit_points", hit_points.into())]
+            }
+        }
+    }
+}
+
+/// Find the lowest attack power the elves need to win without losses, and the outcome of that
+/// battle.
+///
+/// Attack powers are tried in batches which run in parallel, and each trial is aborted as soon as
+/// an elf dies. Since trials can't share the observers, only the battle with the winning attack
+/// power is drawn and emits events.
+///
+/// The search stops at the attack power where elves kill any enemy in one hit, since a higher
+/// attack power can't change the battle.
+pub fn save_the_elves(
+    original: State,
+    visuals: &mut Visuals,
+    events: &mut Events<Event>,
+) -> Result<(u64, Outcome), Error> {
+    let batch = rayon::current_num_threads() as u64;
+
+    let max = original
+        .units
+        .values()
+        .filter(|u| original.factions.is_enemy(Kind::ELF, u.kind))
+        .map(|u| u.hit_points + u.armour)
+        .max()
+        .unwrap_or_default()
+        .max(4);
+
+    for start in (4..=max).step_by(batch as usize) {
+        let trials = (start..u64::min(start + batch, max + 1))
+            .into_par_iter()
+            .map(|ap| {
+                let outcome = original
+                    .with_elf_attack_power(ap)
+                    .simulate_until_death(Kind::ELF)?;
+                Ok((ap, outcome))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // NB: trials are collected in order, so the first one that is won is the lowest power.
+        let (ap, outcome) = match trials
+            .into_iter()
+            .find_map(|(ap, outcome)| Some((ap, outcome?)))
+        {
+            Some(trial) => trial,
+            None => continue,
+        };
+
+        if visuals.is_enabled() || events.is_enabled() {
+            events.emit(
+                0,
+                Event::Battle {
+                    elf_attack_power: ap,
+                },
+            )?;
+
+            original
+                .with_elf_attack_power(ap)
+                .simulate_with(visuals, events)?;
+        }
+
+        return Ok((ap, outcome));
+    }
+
+    bail!(
+        "no attack power saves the elves, up to {} where they kill any enemy in one hit",
+        max
+    );
+}
+
+/// The state in the text format of the puzzle, where the hit points of the units on each row of
+/// the map are annotated to the right of it.
+///
+/// Factions which differ from the defaults are written as a header, see [`Factions`].
+pub struct Annotated<'a>(&'a State);
+
+impl fmt::Display for Annotated<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Annotated(state) = *self;
+        state.factions.write_header(fmt)?;
+
+        for y in 0..state.walls.height() {
+            let mut annotations = Vec::new();
+
+            for x in 0..state.walls.width() {
+                let p = Pos::new(x as i64, y as i64);
+
+                match state.walls[(x, y)] {
+                    true => '#'.fmt(fmt)?,
+                    false => match state.find_unit_by_position(p) {
+                        Some(unit) => {
+                            unit.kind.fmt(fmt)?;
+                            annotations.push(fill(ANNOTATION, &[&unit.kind, &unit.hit_points]));
+                        }
+                        None => '.'.fmt(fmt)?,
+                    },
+                }
+            }
+
+            if !annotations.is_empty() {
+                write!(fmt, "{}{}", SEPARATOR, annotations.join(DELIMITER))?;
+            }
+
+            writeln!(fmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Display<'a>(&'a State);
+
+impl fmt::Display for Display<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Display(state) = *self;
+
+        for y in 0..state.walls.height() {
+            for x in 0..state.walls.width() {
+                let p = Pos::new(x as i64, y as i64);
+
+                match state.walls[(x, y)] {
+                    true => "🧱".fmt(fmt)?,
+                    false => match state.find_unit_by_position(p) {
+                        Some(unit) => match unit.kind {
+                            Kind::GOBLIN => "👹".fmt(fmt)?,
+                            Kind::ELF => "🧝".fmt(fmt)?,
+                            kind => write!(fmt, "{} ", kind)?,
+                        },
+                        None => "⬛".fmt(fmt)?,
+                    },
+                }
+            }
+
+            writeln!(fmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Colors to use when exporting the battle.
+pub fn palette() -> Palette {
+    Palette::new([0x10, 0x10, 0x10])
+        .color('🧱', [0x80, 0x50, 0x30])
+        .color('⬛', [0x30, 0x30, 0x30])
+        .color('👹', [0x40, 0xc0, 0x40])
+        .color('🧝', [0xe0, 0xc0, 0x40])
+}
+
+/// Generate a cave which is `size` tiles wide and tall, with about `size` units in it.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(4);
+    let mut cave = vec![vec!['.'; size + 2]; size + 2];
+
+    cave[0] = vec!['#'; size + 2];
+    cave[size + 1] = vec!['#'; size + 2];
+
+    for row in &mut cave {
+        row[0] = '#';
+        row[size + 1] = '#';
+    }
+
+    let mut open = Vec::new();
+
+    for y in 1..=size {
+        for x in 1..=size {
+            open.push((x, y));
+        }
+    }
+
+    rng.shuffle(&mut open);
+
+    // NB: only place walls which don't touch any other wall, so that the cave stays connected and
+    // units can't get stuck behind each other in narrow passages.
+    for &(x, y) in &open[..open.len() / 8] {
+        let isolated = (y - 1..=y + 1).all(|y| {
+            (x - 1..=x + 1).all(|x| cave[y][x] == '.' || y == 0 || x == 0 || y > size || x > size)
+        });
+
+        if isolated && x > 1 && y > 1 && x < size && y < size {
+            cave[y][x] = '#';
+        }
+    }
+
+    let open = open
+        .into_iter()
+        .filter(|&(x, y)| cave[y][x] == '.')
+        .collect::<Vec<_>>();
+
+    let units = usize::min(size / 2 + 2, open.len());
+    let goblins = rng.below(units / 2) + 1;
+
+    for (i, &(x, y)) in open[..units].iter().enumerate() {
+        cave[y][x] = if i < goblins { 'G' } else { 'E' };
+    }
+
+    let mut out = String::new();
+
+    for row in cave {
+        out.extend(row);
+        out.push('\n');
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Log;
+
+    /// The attack power needed to save the elves, and the outcome of doing so.
+    type Saved = Option<(u64, u64)>;
+
+    /// Examples with their outcome, and what it takes to save the elves if given by the puzzle.
+    const EXAMPLES: &[(&str, u64, Saved)] = &[
+        (input_str!("day15a.txt"), 36334, None),
+        (input_str!("day15b.txt"), 39514, Some((4, 31284))),
+        (input_str!("day15c.txt"), 27755, Some((15, 3478))),
+        (input_str!("day15d.txt"), 28944, Some((12, 6474))),
+        (input_str!("day15e.txt"), 18740, Some((34, 1140))),
+    ];
+
+    #[test]
+    fn test_simulate() -> Result<(), Error> {
+        for &(input, expected, _) in EXAMPLES {
+            assert_eq!(State::load(input)?.simulate()?.score(), expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_outcome() -> Result<(), Error> {
+        let outcome = State::load(input_str!("day15a.txt"))?.simulate()?;
+
+        assert_eq!(outcome.rounds, 37);
+        assert_eq!(outcome.winners, BTreeSet::from([Kind::ELF]));
+
+        let hit_points = outcome
+            .survivors
+            .iter()
+            .map(|(_, hit_points)| *hit_points)
+            .collect::<Vec<_>>();
+
+        assert_eq!(hit_points, vec![200, 197, 185, 200, 200]);
+        assert_eq!(outcome.hit_points(), 982);
+
+        let deaths = vec![(Kind::GOBLIN, 2), (Kind::ELF, 1)];
+        assert_eq!(outcome.deaths, deaths.into_iter().collect());
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_the_elves() -> Result<(), Error> {
+        for &(input, _, expected) in EXAMPLES {
+            if let Some((expected_ap, expected)) = expected {
+                let (ap, outcome) = save_the_elves(
+                    State::load(input)?,
+                    &mut Visuals::noop(),
+                    &mut Events::noop(),
+                )?;
+                assert_eq!(ap, expected_ap);
+                assert_eq!(outcome.score(), expected);
+                assert!(!outcome.deaths.contains_key(&Kind::ELF));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_the_elves_impossible() -> Result<(), Error> {
+        // NB: the goblin kills the elf before it gets to act.
+        let state = State::load("#####\n#GE.#   G(200), E(2)\n#####\n")?;
+        let result = save_the_elves(state, &mut Visuals::noop(), &mut Events::noop());
+
+        let error = result.expect_err("no attack power saves the elves");
+        assert_eq!(
+            error.to_string(),
+            "no attack power saves the elves, up to 200 where they kill any enemy in one hit"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_simulate_until_death() -> Result<(), Error> {
+        let state = State::load(input_str!("day15a.txt"))?;
+        assert_eq!(state.clone().simulate_until_death(Kind::ELF)?, None);
+
+        let outcome = state.clone().simulate_until_death(Kind::GOBLIN)?;
+        assert_eq!(outcome, None);
+
+        let saved = state
+            .with_elf_attack_power(200)
+            .simulate_until_death(Kind::ELF)?;
+        assert_eq!(saved.map(|o| o.winners), Some(BTreeSet::from([Kind::ELF])));
+        Ok(())
+    }
+
+    #[test]
+    fn test_factions() -> Result<(), Error> {
+        let input = "\
+E: 200 hit points, 3 attack power, 2 armour
+T: 300 hit points, 9 attack power
+E and T are allies
+
+#######
+#G.E.T#
+#G....#
+#######
+";
+
+        // the header is written back the same way it was read.
+        let (header, _) = input.split_at(input.find("\n\n").expect("header") + 2);
+        assert!(State::load(input)?
+            .annotated()
+            .to_string()
+            .starts_with(header));
+
+        let log = Log::default();
+        let outcome =
+            State::load(input)?.simulate_with(&mut Visuals::noop(), &mut Events::log(&log))?;
+
+        assert_eq!(outcome.winners, BTreeSet::from([Kind::ELF, Kind::new('T')]));
+        assert_eq!(
+            outcome.deaths,
+            vec![(Kind::GOBLIN, 2)].into_iter().collect()
+        );
+
+        // goblins only deal a single point of damage through the armour of the elf.
+        let damage = log
+            .events()
+            .into_iter()
+            .filter_map(|(_, e)| match e {
+                Event::Attack {
+                    target: 1, damage, ..
+                } => Some(damage),
+                _ => None,
+            })
+            .collect::<BTreeSet<_>>();
+
+        assert_eq!(damage, BTreeSet::from([1]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_factions_header() {
+        let e = State::load("E and T are allies\n\n#E.T#\n").unwrap_err();
+        let e = e.downcast::<ParseError>().expect("parse error");
+        assert_eq!((e.line(), e.message()), (1, "no faction `T`"));
+
+        let e = State::load("G: 200 hit points, 3 attack power\n\n#G.X#\n").unwrap_err();
+        let e = e.downcast::<ParseError>().expect("parse error");
+        assert_eq!((e.line(), e.columns()), (3, 4..5));
+    }
+
+    #[test]
+    fn test_annotated() -> Result<(), Error> {
+        // how the puzzle shows the end of the battle in the first example.
+        let end = "\
+#######
+#...#E#   E(200)
+#E#...#   E(197)
+#.E##.#   E(185)
+#E..#E#   E(200), E(200)
+#.....#
+#######
+";
+
+        let mut state = State::load(input_str!("day15a.txt"))?;
+        assert_eq!(state.simulate_rounds(20)?, None);
+
+        let snapshot = state.annotated().to_string();
+        let mut resumed = State::load(&snapshot)?;
+        assert_eq!(resumed.annotated().to_string(), snapshot);
+
+        let outcome = resumed.simulate()?;
+        assert_eq!((outcome.rounds, outcome.hit_points()), (17, 982));
+        assert_eq!(resumed.annotated().to_string(), end);
+
+        let state = State::load(input_str!("day15b.txt"))?.with_elf_attack_power(4);
+        let snapshot = state.annotated().to_string();
+        assert!(snapshot.starts_with("E: 200 hit points, 4 attack power\n\n"));
+        assert_eq!(State::load(&snapshot)?.annotated().to_string(), snapshot);
+
+        let e = State::load("#G.E#   E(200), G(3)\n").unwrap_err();
+        let e = e.downcast::<ParseError>().expect("parse error");
+        assert_eq!(
+            (e.columns(), e.message()),
+            (9..15, "expected annotation for `G`")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_annotated_malformed() {
+        let cases = [
+            (
+                "#G.E#   G(200), E(1x)\n",
+                (1, 17..22, "expected annotation like `G(200)`"),
+            ),
+            (
+                "#G.E#   G(200),E(200)\n",
+                (1, 9..22, "expected annotation like `G(200)`"),
+            ),
+            (
+                "#G.E#  G(200), E(200)\n",
+                (
+                    1,
+                    6..22,
+                    "expected a row of 5 columns, followed by `   ` and annotations",
+                ),
+            ),
+            (
+                "#####\n #G.E#   G(200), E(200)\n#####\n",
+                (
+                    2,
+                    6..24,
+                    "expected a row of 5 columns, followed by `   ` and annotations",
+                ),
+            ),
+            (
+                "#G.E#   G(0), E(200)\n",
+                (1, 9..13, "units must have hit points left"),
+            ),
+        ];
+
+        for (input, expected) in cases.iter().cloned() {
+            let e = State::load(input).unwrap_err();
+            let e = e.downcast::<ParseError>().expect("parse error");
+            assert_eq!((e.line(), e.columns(), e.message()), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_next_step_agrees_with_search() {
+        /// Find the first step by searching for the square to move to, and then following the
+        /// shortest path to it.
+        fn search(state: &State, from: Pos, targets: &HashSet<Pos>) -> Option<Pos> {
+            let is_free = |p: &Pos| {
+                let wall = state.walls.get_pos(*p);
+                wall == Some(&false) && !state.is_unit_at(*p)
+            };
+
+            let (square, paths) = search::nearest(
+                Some(from),
+                |p| p.neighbours4().filter(is_free),
+                |p| *p != from && p.neighbours4().any(|n| targets.contains(&n)),
+                |p| *p,
+            )?;
+
+            paths.path(&square)?.get(1).cloned()
+        }
+
+        gen::check(
+            50,
+            |rng| (rng.next_u64(), rng.below(16) + 4),
+            |&(seed, size)| {
+                let mut state = State::load(&generate(&mut gen::Rng::new(seed), size))?;
+                // NB: shared across enough searches that its grids are also cleared.
+                let mut scratch = Scratch::new(&state.walls);
+
+                // NB: compare a few rounds in, once units have started to crowd each other.
+                for _ in 0..30 {
+                    for id in state.find_priority_units() {
+                        let unit = state.find_unit(id)?;
+                        let from = state.find_unit_position(id)?;
+
+                        let targets = state
+                            .position_by_unit
+                            .iter()
+                            .filter(|(other, _)| state.is_target(unit, &state.units[*other]))
+                            .map(|(_, p)| *p)
+                            .collect::<HashSet<_>>();
+
+                        let a = state.find_next_step(&mut scratch, from, targets.iter().cloned());
+                        let b = search(&state, from, &targets);
+
+                        if a != b {
+                            bail!(
+                                "unit {} at {} steps to {:?}, but expected {:?}",
+                                id,
+                                from,
+                                a,
+                                b
+                            );
+                        }
+                    }
+
+                    if state.simulate_rounds(1)?.is_some() {
+                        break;
+                    }
+                }
+
+                Ok(())
+            },
+        );
+    }
+
+    #[test]
+    fn test_events() -> Result<(), Error> {
+        let log = Log::default();
+        let outcome = State::load(input_str!("day15a.txt"))?
+            .simulate_with(&mut Visuals::noop(), &mut Events::log(&log))?;
+        assert_eq!(outcome.score(), 36334);
+
+        let events = log.events();
+
+        // the goblin in the top-left corner acts first, and is already next to an elf.
+        assert_eq!(
+            events.first(),
+            Some(&(
+                0,
+                Event::Attack {
+                    unit: 0,
+                    target: 2,
+                    damage: 3,
+                    hit_points: 197
+                }
+            ))
+        );
+
+        let kills = events
+            .iter()
+            .filter(|(_, e)| {
+                matches!(
+                    e,
+                    Event::Kill {
+                        kind: Kind::ELF,
+                        ..
+                    }
+                )
+            })
+            .count();
+
+        assert_eq!(kills, 1);
+        assert_eq!(
+            events.last(),
+            Some(&(
+                37,
+                Event::End {
+                    rounds: 37,
+                    hit_points: 982
+                }
+            ))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,479 @@
+use crate::visuals::{Frame, Style, Visuals};
+use crate::*;
+use std::fmt;
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Manual;
+    type Part1 = usize;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut it = input.lines();
+
+        let mut tests = Vec::new();
+
+        while let Some(test) = Test::decode(&mut it) {
+            tests.push(test);
+        }
+
+        let program = it.flat_map(Instruction::decode).collect();
+        Ok(Manual { tests, program })
+    }
+
+    fn part1(manual: &Self::Input) -> Result<usize, Error> {
+        let mut count = 0;
+
+        for test in &manual.tests {
+            if test.matches()?.len() >= 3 {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    fn part2(manual: &Self::Input) -> Result<u64, Error> {
+        run(&manual.decoder()?, &manual.program, &mut Visuals::noop())
+    }
+}
+
+/// The device manual, with samples of instructions and the program to run.
+#[derive(Debug)]
+pub struct Manual {
+    tests: Vec<Test>,
+    program: Vec<Instruction>,
+}
+
+impl Manual {
+    /// Figure out which op-code corresponds to each op number by looking at the samples.
+    pub fn decoder(&self) -> Result<Decoder, Error> {
+        let mut registry = Registry::default();
+
+        // NB: all op codes are initially possible for all op numbers.
+        for c in 0..16u64 {
+            for op in OpCode::variants() {
+                registry.0.entry(c).or_default().insert(op);
+            }
+        }
+
+        for test in &self.tests {
+            let matches = test.matches()?;
+
+            if let Some(codes) = registry.0.get_mut(&test.inst.op_code) {
+                codes.retain(|op| matches.contains(op));
+            }
+
+            // definitive proof that a specific op-code is the one.
+            if matches.len() == 1 {
+                if let Some(op) = matches.iter().cloned().next() {
+                    if let Some(values) = registry.0.get_mut(&test.inst.op_code) {
+                        values.clear();
+                        values.insert(op);
+                    }
+                }
+            }
+        }
+
+        let known = registry.regress();
+
+        if known.len() != 16 {
+            bail!("could only decode {} out of 16 op-codes", known.len());
+        }
+
+        Ok(Decoder::new(known))
+    }
+
+    /// Run the program in the manual.
+    pub fn run(&self, visuals: &mut Visuals) -> Result<u64, Error> {
+        run(&self.decoder()?, &self.program, visuals)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Device([u64; 4]);
+
+impl Device {
+    /// Try to decode a device.
+    /// Devices take the form `[a, b, c, d]` representing all registries.
+    pub fn decode(state: &str) -> Option<Device> {
+        let mut it = state
+            .trim_matches(|c| c == '[' || c == ']')
+            .split(", ")
+            .flat_map(|d| str::parse(d).ok());
+
+        Some(Device([it.next()?, it.next()?, it.next()?, it.next()?]))
+    }
+
+    pub fn reg(&mut self, reg: u64) -> Result<&mut u64, Error> {
+        match self.0.get_mut(reg as usize) {
+            Some(reg) => Ok(reg),
+            None => bail!("no such register: {}", reg),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OpCode {
+    Addr,
+    Addi,
+    Mulr,
+    Muli,
+    Banr,
+    Bani,
+    Borr,
+    Bori,
+    Setr,
+    Seti,
+    Gtir,
+    Gtri,
+    Gtrr,
+    Eqir,
+    Eqri,
+    Eqrr,
+}
+
+impl fmt::Display for OpCode {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::OpCode::*;
+
+        let name = match *self {
+            Addr => "addr",
+            Addi => "addi",
+            Mulr => "mulr",
+            Muli => "muli",
+            Banr => "banr",
+            Bani => "bani",
+            Borr => "borr",
+            Bori => "bori",
+            Setr => "setr",
+            Seti => "seti",
+            Gtir => "gtir",
+            Gtri => "gtri",
+            Gtrr => "gtrr",
+            Eqir => "eqir",
+            Eqri => "eqri",
+            Eqrr => "eqrr",
+        };
+
+        name.fmt(fmt)
+    }
+}
+
+impl OpCode {
+    /// Iterate over all variants.
+    fn variants() -> impl Iterator<Item = OpCode> {
+        use self::OpCode::*;
+
+        vec![
+            Addr, Addi, Mulr, Muli, Banr, Bani, Borr, Bori, Setr, Seti, Gtir, Gtri, Gtrr, Eqir,
+            Eqri, Eqrr,
+        ]
+        .into_iter()
+    }
+
+    fn apply(&self, d: &mut Device, inputs: &[u64; 2], o: u64) -> Result<(), Error> {
+        use self::OpCode::*;
+
+        let [a, b] = *inputs;
+
+        *d.reg(o)? = match *self {
+            Addr => *d.reg(a)? + *d.reg(b)?,
+            Addi => *d.reg(a)? + b,
+            Mulr => *d.reg(a)? * *d.reg(b)?,
+            Muli => *d.reg(a)? * b,
+            Banr => *d.reg(a)? & *d.reg(b)?,
+            Bani => *d.reg(a)? & b,
+            Borr => *d.reg(a)? | *d.reg(b)?,
+            Bori => *d.reg(a)? | b,
+            Setr => *d.reg(a)?,
+            Seti => a,
+            Gtir => {
+                if a > *d.reg(b)? {
+                    1
+                } else {
+                    0
+                }
+            }
+            Gtri => {
+                if *d.reg(a)? > b {
+                    1
+                } else {
+                    0
+                }
+            }
+            Gtrr => {
+                if *d.reg(a)? > *d.reg(b)? {
+                    1
+                } else {
+                    0
+                }
+            }
+            Eqir => {
+                if a == *d.reg(b)? {
+                    1
+                } else {
+                    0
+                }
+            }
+            Eqri => {
+                if *d.reg(a)? == b {
+                    1
+                } else {
+                    0
+                }
+            }
+            Eqrr => {
+                if *d.reg(a)? == *d.reg(b)? {
+                    1
+                } else {
+                    0
+                }
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// An instruction.
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    op_code: u64,
+    inputs: [u64; 2],
+    output: u64,
+}
+
+impl Instruction {
+    pub fn decode(state: &str) -> Option<Instruction> {
+        let mut it = state.split(" ").flat_map(|d| str::parse(d).ok());
+
+        Some(Instruction {
+            op_code: it.next()?,
+            inputs: [it.next()?, it.next()?],
+            output: it.next()?,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Registry(HashMap<u64, HashSet<OpCode>>);
+
+impl Registry {
+    /// Try to reduce the number of definitive matches.
+    pub fn regress(&mut self) -> Vec<(u64, OpCode)> {
+        let mut known = Vec::new();
+        let mut current = 0;
+
+        self.known(&mut known);
+
+        while current != known.len() {
+            current = known.len();
+
+            for (known_num, known_op) in known.iter().cloned() {
+                for (num, values) in self.0.iter_mut() {
+                    if *num == known_num {
+                        values.clear();
+                        values.insert(known_op);
+                        continue;
+                    }
+
+                    values.remove(&known_op);
+                }
+            }
+
+            known.clear();
+            self.known(&mut known);
+        }
+
+        known
+    }
+
+    /// Extract exactly known op-codes.
+    fn known(&self, known: &mut Vec<(u64, OpCode)>) {
+        for (key, value) in &self.0 {
+            if value.len() == 1 {
+                if let Some(op) = value.iter().cloned().next() {
+                    known.push((*key, op));
+                }
+            }
+        }
+    }
+}
+
+pub struct Decoder {
+    codes: HashMap<u64, OpCode>,
+}
+
+impl Decoder {
+    pub fn new(codes: impl IntoIterator<Item = (u64, OpCode)>) -> Decoder {
+        Decoder {
+            codes: codes.into_iter().collect(),
+        }
+    }
+
+    pub fn decode(&self, code: u64) -> Result<OpCode, Error> {
+        match self.codes.get(&code).cloned() {
+            Some(op) => Ok(op),
+            None => bail!("no such op: {}", code),
+        }
+    }
+}
+
+/// Run the given program.
+pub fn run(
+    decoder: &Decoder,
+    program: &[Instruction],
+    visuals: &mut Visuals,
+) -> Result<u64, Error> {
+    let mut device = Device::default();
+    let mut last = VecDeque::new();
+
+    for inst in program.iter().cloned() {
+        let before = device.clone();
+
+        let op = decoder.decode(inst.op_code)?;
+        op.apply(&mut device, &inst.inputs, inst.output)?;
+
+        if visuals.is_enabled() {
+            if last.len() == 10 {
+                last.pop_front();
+            }
+
+            last.push_back((op, inst));
+            visuals.draw(|f| draw(f, &last, &before, &device))?;
+        }
+    }
+
+    visuals.done(format!("Result is {}", device.reg(0)?))?;
+    Ok(*device.reg(0)?)
+}
+
+/// Draw the last instructions which were run, and the registers that they changed.
+fn draw(f: &mut Frame, last: &VecDeque<(OpCode, Instruction)>, before: &Device, device: &Device) {
+    f.print_styled(0, 2, Style::Underline, "Instructions");
+    f.print_styled(0, 16, Style::Underline, "Registers");
+
+    for (line, (op, inst)) in last.iter().enumerate() {
+        let [a, b] = inst.inputs;
+        let text = format!("{} {}, {}, {}", op, a, b, inst.output);
+
+        if line + 1 == last.len() {
+            f.print(line + 1, 1, ">");
+            f.print_styled(line + 1, 2, Style::Standout, text);
+        } else {
+            f.print(line + 1, 2, text);
+        }
+    }
+
+    for (line, (a, b)) in before.0.iter().zip(device.0.iter()).enumerate() {
+        let style = if a != b {
+            Style::Standout
+        } else {
+            Style::Normal
+        };
+        f.print(line + 1, 16, format!("{} = ", line));
+        f.print_styled(line + 1, 20, style, b);
+    }
+}
+
+#[derive(Debug)]
+pub struct Test {
+    before: Device,
+    inst: Instruction,
+    after: Device,
+}
+
+impl Test {
+    /// Find all op-codes which behave as the sample.
+    pub fn matches(&self) -> Result<HashSet<OpCode>, Error> {
+        let mut matches = HashSet::new();
+
+        for op in OpCode::variants() {
+            let mut device = self.before.clone();
+            op.apply(&mut device, &self.inst.inputs, self.inst.output)?;
+
+            if device == self.after {
+                matches.insert(op);
+            }
+        }
+
+        Ok(matches)
+    }
+
+    fn decode<'a>(it: &mut impl Iterator<Item = &'a str>) -> Option<Test> {
+        let before = it.next()?;
+
+        if before.is_empty() {
+            return None;
+        }
+
+        let inst = it.next()?;
+        let after = it.next()?;
+        let blank = it.next()?;
+
+        if !before.starts_with("Before: ") {
+            return None;
+        }
+
+        if !after.starts_with("After: ") {
+            return None;
+        }
+
+        if !blank.is_empty() {
+            return None;
+        }
+
+        let before = Device::decode(before.split(": ").nth(1)?.trim())?;
+        let inst = Instruction::decode(inst)?;
+        let after = Device::decode(after.split(": ").nth(1)?.trim())?;
+
+        Some(Test {
+            before,
+            inst,
+            after,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() -> Result<(), Error> {
+        let manual = Day16::parse("Before: [3, 2, 1, 1]\n9 2 1 2\nAfter:  [3, 2, 2, 1]\n\n")?;
+
+        let matches = manual.tests[0].matches()?;
+        let expected = vec![OpCode::Mulr, OpCode::Addi, OpCode::Seti];
+        assert_eq!(matches, expected.into_iter().collect::<HashSet<_>>());
+        assert_eq!(Day16::part1(&manual)?, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_without_visuals() -> Result<(), Error> {
+        let manual = Day16::parse(input_str!("day16.txt"))?;
+        assert_eq!(manual.run(&mut Visuals::noop())?, Day16::part2(&manual)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scripted_run() -> Result<(), Error> {
+        use crate::visuals::{Quit, Recording, Script};
+
+        let manual = Day16::parse(input_str!("day16.txt"))?;
+
+        let recording = Recording::default();
+        let script = Script::parse("step 4, quit")?;
+        let mut visuals = Visuals::recorder(&recording).interactive().script(script);
+        assert!(manual.run(&mut visuals).unwrap_err().is::<Quit>());
+        assert_eq!(recording.frames().len(), 5);
+
+        let script = Script::parse("step 2\ncontinue")?;
+        let mut visuals = Visuals::recorder(&recording).interactive().script(script);
+        assert_eq!(manual.run(&mut visuals)?, Day16::part2(&manual)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,494 @@
+use crate::events::{self, Events, Value};
+use crate::export::Palette;
+use crate::gen::Rng;
+use crate::visuals::{Frame, Visuals};
+use crate::*;
+
+use std::ops::RangeInclusive;
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Tiles;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Tiles::load(input)
+    }
+
+    fn part1(tiles: &Self::Input) -> Result<usize, Error> {
+        Ok(solve(&mut tiles.clone(), &mut Events::noop())?.0)
+    }
+
+    fn part2(tiles: &Self::Input) -> Result<usize, Error> {
+        Ok(solve(&mut tiles.clone(), &mut Events::noop())?.1)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Clay,
+    Still,
+    Flowing,
+    Empty,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tiles {
+    source: (i64, i64),
+    /// The position of the top-left corner of the grid.
+    origin: (i64, i64),
+    tiles: Grid<Tile>,
+    ry: RangeInclusive<i64>,
+    ry_with_source: RangeInclusive<i64>,
+}
+
+impl Tiles {
+    pub fn load(input: &str) -> Result<Tiles, Error> {
+        let mut clay = Vec::new();
+
+        // water source
+        let source = (500, 0);
+
+        let mut bounds = Bounds2::default();
+
+        for (n, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (x, y) = if line.starts_with("y=") {
+                let (y, x0, x1) = scan!(line = n + 1, line, "y={}, x={}..{}", i64, i64, i64)?;
+                ((x0, x1), (y, y))
+            } else {
+                let (x, y0, y1) = scan!(line = n + 1, line, "x={}, y={}..{}", i64, i64, i64)?;
+                ((x, x), (y0, y1))
+            };
+
+            for x in x.0..=x.1 {
+                for y in y.0..=y.1 {
+                    clay.push((x, y));
+                    bounds.sample((x, y));
+                }
+            }
+        }
+
+        if bounds.is_empty() {
+            bail!("no clay in input");
+        }
+
+        let mut with_source = bounds.clone();
+        with_source.sample(source);
+
+        // NB: water can flow over the edges of the outermost clay.
+        let grid = with_source.grow(1);
+        let (origin, _) = grid.get().ok_or_else(|| format_err!("no bounds"))?;
+        let width = grid.width().ok_or_else(|| format_err!("no width"))? as usize;
+        let height = grid.height().ok_or_else(|| format_err!("no height"))? as usize;
+
+        let mut tiles = Tiles {
+            source,
+            origin,
+            tiles: Grid::new(width, height, Tile::Empty),
+            ry: bounds.y().range_inclusive(),
+            ry_with_source: with_source.y().range_inclusive(),
+        };
+
+        for pos in clay {
+            let c = tiles
+                .coord(pos)
+                .ok_or_else(|| format_err!("clay out of bounds"))?;
+            tiles.tiles[c] = Tile::Clay;
+        }
+
+        Ok(tiles)
+    }
+
+    /// Convert a position to a coordinate in the grid, if it is inside of it.
+    fn coord(&self, (x, y): (i64, i64)) -> Option<Coord> {
+        let (x0, y0) = self.origin;
+
+        if x < x0 || y < y0 {
+            return None;
+        }
+
+        let c = ((x - x0) as usize, (y - y0) as usize);
+
+        if !self.tiles.contains(c) {
+            return None;
+        }
+
+        Some(c)
+    }
+
+    /// Visualize the tiles.
+    pub fn visualize(&self, visuals: &mut Visuals) -> Result<(), Error> {
+        visuals.draw(|f| self.draw(f))?;
+        visuals.done("")
+    }
+
+    /// Draw the tiles.
+    fn draw(&self, f: &mut Frame) {
+        let x0 = self.origin.0;
+        let x1 = x0 + self.tiles.width() as i64 - 1;
+
+        for (row, y) in self.ry.clone().enumerate() {
+            let line = (x0..=x1)
+                .map(|x| {
+                    if (x, y) == self.source {
+                        return '+';
+                    }
+
+                    match self.get((x, y)) {
+                        Some(Tile::Clay) => '#',
+                        Some(Tile::Still) => '~',
+                        Some(Tile::Flowing) => '|',
+                        Some(Tile::Empty) => '.',
+                        None => '?',
+                    }
+                })
+                .collect::<String>();
+
+            f.print(row, 0, line);
+        }
+    }
+
+    /// Check what is on the given tile.
+    ///
+    /// Returns `None` if the request is out of bounds, otherwise returns the tile.
+    pub fn get(&self, (x, y): (i64, i64)) -> Option<Tile> {
+        if !self.ry_with_source.contains(&y) {
+            return None;
+        }
+
+        Some(
+            self.coord((x, y))
+                .map(|c| self.tiles[c])
+                .unwrap_or(Tile::Empty),
+        )
+    }
+
+    /// Fill x range with something.
+    pub fn fill_x(&mut self, x: RangeInclusive<i64>, y: i64, tile: Tile) -> Result<(), Error> {
+        if !self.ry.contains(&y) {
+            return Ok(());
+        }
+
+        for x in x {
+            match self.replace((x, y), tile)? {
+                Tile::Empty | Tile::Flowing => {}
+                existing => bail!("Already had thing `{:?}` at tile {:?}", existing, (x, y)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fill y range with something.
+    pub fn fill_y(&mut self, x: i64, y: RangeInclusive<i64>, tile: Tile) -> Result<(), Error> {
+        for y in y {
+            if !self.ry.contains(&y) {
+                continue;
+            }
+
+            match self.replace((x, y), tile)? {
+                Tile::Empty => {}
+                existing => bail!("Already had thing `{:?}` at tile {:?}", existing, (x, y)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replace the given tile, returning what was there before.
+    fn replace(&mut self, pos: (i64, i64), tile: Tile) -> Result<Tile, Error> {
+        let c = self
+            .coord(pos)
+            .ok_or_else(|| format_err!("tile out of bounds: {:?}", pos))?;
+
+        Ok(std::mem::replace(&mut self.tiles[c], tile))
+    }
+}
+
+/// Events emitted while filling the tiles with water.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// Water fell down a column, and flowed out of bounds if `escaped` is set.
+    Fall {
+        x: i64,
+        y: RangeInclusive<i64>,
+        escaped: bool,
+    },
+    /// Water filled a row, where still water is settled between clay.
+    Fill {
+        x: RangeInclusive<i64>,
+        y: i64,
+        still: bool,
+    },
+}
+
+impl events::Event for Event {
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Fall { .. } => "fall",
+            Event::Fill { .. } => "fill",
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, Value)> {
+        match self {
+            Event::Fall { x, y, escaped } => vec![
+                ("x", (*x).into()),
+                ("y0", (*y.start()).into()),
+                ("y1", (*y.end()).into()),
+                ("escaped", (*escaped).into()),
+            ],
+            Event::Fill { x, y, still } => vec![
+                ("x0", (*x.start()).into()),
+                ("x1", (*x.end()).into()),
+                ("y", (*y).into()),
+                ("still", (*still).into()),
+            ],
+        }
+    }
+}
+
+/// Fill the tiles with water, returning how many tiles the water reaches and how many are still.
+///
+/// Events are emitted with the pass over the queues of falling and spreading water that they
+/// happened in.
+pub fn solve(tiles: &mut Tiles, events: &mut Events<Event>) -> Result<(usize, usize), Error> {
+    // queue of water "drops"
+    let mut drop_queue = VecDeque::new();
+    drop_queue.push_back(tiles.source);
+
+    let mut floor_queue = VecDeque::new();
+    let mut tick = 0;
+
+    while !drop_queue.is_empty() || !floor_queue.is_empty() {
+        while let Some((x, y)) = drop_queue.pop_front() {
+            match scan_down(tiles, (x, y)) {
+                Some((pos, tile)) => {
+                    tiles.fill_y(x, y..=pos.1, Tile::Flowing)?;
+
+                    let event = Event::Fall {
+                        x,
+                        y: y..=pos.1,
+                        escaped: false,
+                    };
+
+                    events.emit(tick, event)?;
+
+                    if tile != Tile::Flowing {
+                        floor_queue.push_back(pos);
+                    }
+                }
+                // NB: went out of bounds
+                None => {
+                    tiles.fill_y(x, y..=*tiles.ry.end(), Tile::Flowing)?;
+
+                    let event = Event::Fall {
+                        x,
+                        y: y..=*tiles.ry.end(),
+                        escaped: true,
+                    };
+
+                    events.emit(tick, event)?;
+                }
+            }
+        }
+
+        // digest the floor queue.
+        while let Some((x, y)) = floor_queue.pop_front() {
+            // we are on a floor that is already filled, keep trying!
+            if let Some(Tile::Still) = tiles.get((x, y)) {
+                floor_queue.push_back((x, y - 1));
+                continue;
+            }
+
+            let left = scan_floor(tiles, (x, y), -1)?;
+            let right = scan_floor(tiles, (x, y), 1)?;
+
+            match (left, right) {
+                // bounded.
+                ((Some(Tile::Clay), left), (Some(Tile::Clay), right)) => {
+                    tiles.fill_x(left.0..=right.0, y, Tile::Still)?;
+
+                    let event = Event::Fill {
+                        x: left.0..=right.0,
+                        y,
+                        still: true,
+                    };
+
+                    events.emit(tick, event)?;
+                    floor_queue.push_back((x, y - 1));
+                }
+                (left, right) => {
+                    tiles.fill_x((left.1).0..=(right.1).0, y, Tile::Flowing)?;
+
+                    let event = Event::Fill {
+                        x: (left.1).0..=(right.1).0,
+                        y,
+                        still: false,
+                    };
+
+                    events.emit(tick, event)?;
+
+                    for m in [left, right] {
+                        match m {
+                            // NB: empty tile is another position to drop from.
+                            (Some(Tile::Empty), (x, y)) => {
+                                drop_queue.push_back((x, y + 1));
+                            }
+                            (Some(Tile::Clay), _) | (Some(Tile::Flowing), _) | (None, _) => {}
+                            other => bail!("Unexpected tile: {:?}", other),
+                        }
+                    }
+                }
+            }
+        }
+
+        tick += 1;
+    }
+
+    // NB: just to be safe, remove the source.
+    if let Some(c) = tiles.coord(tiles.source) {
+        tiles.tiles[c] = Tile::Empty;
+    }
+
+    let part1 = tiles
+        .tiles
+        .values()
+        .cloned()
+        .map(|t| match t {
+            Tile::Flowing | Tile::Still => 1,
+            _ => 0,
+        })
+        .sum();
+
+    let part2 = tiles
+        .tiles
+        .values()
+        .cloned()
+        .map(|t| match t {
+            Tile::Still => 1,
+            _ => 0,
+        })
+        .sum();
+
+    return Ok((part1, part2));
+
+    /// Scan floor in some direction.
+    ///
+    /// Returns the coordinates and `None` if we hit a wall, the returned coordinates correspond to
+    /// the last coordinates that had an open tile.
+    ///
+    /// Returns the open coordinate in case we no longer have a floor.
+    /// The open coordinate is the coordinate at which the floor stopped.
+    ///
+    /// Otherwise, returns `None`.
+    fn scan_floor(
+        tiles: &Tiles,
+        (mut x, y): (i64, i64),
+        dir: i64,
+    ) -> Result<(Option<Tile>, (i64, i64)), Error> {
+        loop {
+            match tiles.get((x + dir, y)) {
+                Some(Tile::Clay) => return Ok((Some(Tile::Clay), (x, y))),
+                Some(Tile::Still) => bail!("Encountered unexpected still tile at {:?}", (x, y)),
+                _ => {}
+            }
+
+            match tiles.get((x, y + 1)) {
+                Some(Tile::Clay) | Some(Tile::Still) => {}
+                tile => return Ok((tile, (x, y))),
+            }
+
+            x += dir;
+        }
+    }
+
+    fn scan_down(tiles: &Tiles, (x, mut y): (i64, i64)) -> Option<((i64, i64), Tile)> {
+        loop {
+            match tiles.get((x, y)) {
+                Some(Tile::Flowing) => return Some(((x, y - 1), Tile::Flowing)),
+                Some(Tile::Empty) => {}
+                Some(tile) => return Some(((x, y - 1), tile)),
+                None => return None,
+            }
+
+            y += 1;
+        }
+    }
+}
+
+/// Colors to use when exporting the tiles.
+pub fn palette() -> Palette {
+    Palette::new([0xf0, 0xe0, 0xc0])
+        .color('#', [0x80, 0x50, 0x30])
+        .color('~', [0x20, 0x40, 0xc0])
+        .color('|', [0x60, 0xa0, 0xf0])
+        .color('+', [0xff, 0x00, 0x00])
+}
+
+/// Generate about `size` veins of clay, spread out below the spring.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::new();
+    let mut veins = 0;
+
+    // NB: every bucket or platform is placed in a separate cell so that they don't overlap.
+    for cell in 0.. {
+        if veins >= size.max(1) {
+            break;
+        }
+
+        let x = 479 + (cell % 3) * 14 + rng.range(0..4);
+        let y = 2 + (cell / 3) * 12 + rng.range(0..4);
+        let w = rng.range(2..9);
+
+        if rng.chance(0.25) {
+            out.push_str(&format!("y={}, x={}..{}\n", y, x, x + w));
+            veins += 1;
+            continue;
+        }
+
+        let (top_left, top_right) = (y + rng.range(0..3), y + rng.range(0..3));
+        let bottom = y + 6;
+        out.push_str(&format!("x={}, y={}..{}\n", x, top_left, bottom));
+        out.push_str(&format!("x={}, y={}..{}\n", x + w, top_right, bottom));
+        out.push_str(&format!("y={}, x={}..{}\n", bottom, x, x + w));
+        veins += 3;
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Log;
+
+    #[test]
+    fn test_example() -> Result<(), Error> {
+        let mut tiles = Tiles::load(input_str!("day17a.txt"))?;
+        let log = Log::default();
+        assert_eq!(solve(&mut tiles, &mut Events::log(&log))?, (57, 29));
+
+        let still = log
+            .events()
+            .into_iter()
+            .filter_map(|(_, e)| match e {
+                Event::Fill { x, still: true, .. } => Some(x.count()),
+                _ => None,
+            })
+            .sum::<usize>();
+
+        assert_eq!(still, 29);
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use crate::export::Palette;
+use crate::visuals::{Frame, Visuals};
+use crate::*;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Grid<Tile>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        load(input)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<usize, Error> {
+        run(&mut grid.clone(), &mut Visuals::noop(), 10)
+    }
+
+    fn part2(grid: &Self::Input) -> Result<usize, Error> {
+        run(&mut grid.clone(), &mut Visuals::noop(), 1_000_000_000)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tile {
+    Wooded,
+    Lumberyard,
+    Open,
+}
+
+impl Tile {
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Tile::Wooded => "|",
+            Tile::Lumberyard => "#",
+            Tile::Open => ".",
+        }
+    }
+}
+
+/// Load the initial state of the area.
+pub fn load(input: &str) -> Result<Grid<Tile>, Error> {
+    Grid::parse(input, |_, c| {
+        Ok(match c {
+            '#' => Tile::Lumberyard,
+            '|' => Tile::Wooded,
+            '.' => Tile::Open,
+            o => bail!("Unsupported tile: {}", o),
+        })
+    })
+}
+
+/// The total resource value of the area.
+pub fn resources(grid: &Grid<Tile>) -> usize {
+    let mut wooded = 0;
+    let mut lumberyards = 0;
+
+    for tile in grid.values() {
+        match tile {
+            Tile::Wooded => wooded += 1,
+            Tile::Lumberyard => lumberyards += 1,
+            Tile::Open => {}
+        }
+    }
+
+    wooded * lumberyards
+}
+
+/// Run the given number of minutes, returning the resource value at the end.
+///
+/// The area eventually repeats itself, which is used to skip ahead.
+pub fn run(grid: &mut Grid<Tile>, visuals: &mut Visuals, count: usize) -> Result<usize, Error> {
+    visuals.draw(|f| draw(f, 0, grid))?;
+
+    let mut iter = 0;
+
+    // NB: the sequence already keeps every grid, so only a hash of each is used as its key.
+    let seq = cycle::find(
+        grid.clone(),
+        |grid| {
+            iter += 1;
+            let next = tick(grid);
+            visuals.draw(|f| draw(f, iter, &next))?;
+            Ok(next)
+        },
+        |grid| {
+            let mut hasher = DefaultHasher::new();
+            grid.hash(&mut hasher);
+            hasher.finish()
+        },
+        count,
+    )?;
+
+    let (last, _) = seq
+        .get(count)
+        .ok_or_else(|| format_err!("result not found"))?;
+
+    *grid = last.clone();
+    visuals.done(format!("Result is {}", resources(grid)))?;
+    Ok(resources(grid))
+}
+
+/// Calculate what the area looks like after one minute.
+fn tick(grid: &Grid<Tile>) -> Grid<Tile> {
+    grid.map(|pos, tile| {
+        use self::Tile::*;
+
+        let mut wooden = 0;
+        let mut lumberyards = 0;
+
+        for n in grid.neighbours8(pos) {
+            match grid[n] {
+                Wooded => wooden += 1,
+                Lumberyard => lumberyards += 1,
+                Open => {}
+            }
+        }
+
+        match *tile {
+            Open if wooden >= 3 => Wooded,
+            Wooded if lumberyards >= 3 => Lumberyard,
+            Lumberyard if lumberyards >= 1 && wooden >= 1 => Lumberyard,
+            Lumberyard => Open,
+            tile => tile,
+        }
+    })
+}
+
+/// Draw the area after the given number of minutes.
+fn draw(f: &mut Frame, iter: usize, grid: &Grid<Tile>) {
+    f.line(format!("Iter: {}", iter));
+
+    for ((x, y), tile) in grid.iter() {
+        f.print(y + 1, x, tile.as_str());
+    }
+}
+
+/// Colors to use when exporting the area.
+pub fn palette() -> Palette {
+    Palette::new([0x10, 0x10, 0x10])
+        .color('.', [0xc0, 0xb0, 0x80])
+        .color('|', [0x20, 0x80, 0x20])
+        .color('#', [0x80, 0x50, 0x30])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() -> Result<(), Error> {
+        let mut grid = load(input_str!("day18a.txt"))?;
+        assert_eq!(run(&mut grid, &mut Visuals::noop(), 10)?, 1147);
+        Ok(())
+    }
+}
@@ -0,0 +1,454 @@
+use crate::visuals::{Frame, Style, Visuals};
+use crate::*;
+use std::fmt;
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    /// The program to run.
+    type Input = String;
+    type Part1 = i64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(input.to_string())
+    }
+
+    fn part1(program: &Self::Input) -> Result<i64, Error> {
+        solve(&mut Visuals::noop(), program.lines(), 0)
+    }
+
+    fn part2(program: &Self::Input) -> Result<u64, Error> {
+        Ok(part2(find_target(program.lines(), 1)?))
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Device {
+    registers: [i64; 6],
+    /// Which register contains the current instruction.
+    ip: usize,
+}
+
+impl Device {
+    /// Load a device from the `#ip` statement at the start of a program.
+    pub fn load<'a>(it: &mut impl Iterator<Item = &'a str>) -> Result<Device, Error> {
+        let ip = it
+            .next()
+            .and_then(|s| {
+                if s.starts_with("#ip") {
+                    str::parse(s.split(" ").nth(1)?).ok()
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| format_err!("expected #ip statement"))?;
+
+        Ok(Device {
+            ip,
+            ..Device::default()
+        })
+    }
+
+    pub fn ip(&mut self) -> Result<&mut i64, Error> {
+        match self.registers.get_mut(self.ip) {
+            Some(reg) => Ok(reg),
+            None => bail!("no ip register: {}", self.ip),
+        }
+    }
+
+    pub fn reg(&mut self, reg: i64) -> Result<&mut i64, Error> {
+        match self.registers.get_mut(reg as usize) {
+            Some(reg) => Ok(reg),
+            None => bail!("no such register: {}", reg),
+        }
+    }
+
+    fn reg_name(&self, reg: i64) -> String {
+        if reg as usize == self.ip {
+            String::from("%ip")
+        } else {
+            format!("%{}", ['a', 'b', 'c', 'd', 'e', 'f'][reg as usize])
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OpCode {
+    Addr,
+    Addi,
+    Mulr,
+    Muli,
+    Banr,
+    Bani,
+    Borr,
+    Bori,
+    Setr,
+    Seti,
+    Gtir,
+    Gtri,
+    Gtrr,
+    Eqir,
+    Eqri,
+    Eqrr,
+}
+
+impl fmt::Display for OpCode {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use self::OpCode::*;
+
+        let name = match *self {
+            Addr => "addr",
+            Addi => "addi",
+            Mulr => "mulr",
+            Muli => "muli",
+            Banr => "banr",
+            Bani => "bani",
+            Borr => "borr",
+            Bori => "bori",
+            Setr => "setr",
+            Seti => "seti",
+            Gtir => "gtir",
+            Gtri => "gtri",
+            Gtrr => "gtrr",
+            Eqir => "eqir",
+            Eqri => "eqri",
+            Eqrr => "eqrr",
+        };
+
+        name.fmt(fmt)
+    }
+}
+
+impl OpCode {
+    pub fn names(&self, d: &Device, inputs: [i64; 2]) -> [String; 2] {
+        use self::OpCode::*;
+
+        let [a, b] = inputs;
+
+        match *self {
+            Addr => [d.reg_name(a), d.reg_name(b)],
+            Addi => [d.reg_name(a), b.to_string()],
+            Mulr => [d.reg_name(a), d.reg_name(b)],
+            Muli => [d.reg_name(a), b.to_string()],
+            Banr => [d.reg_name(a), d.reg_name(b)],
+            Bani => [d.reg_name(a), b.to_string()],
+            Borr => [d.reg_name(a), d.reg_name(b)],
+            Bori => [d.reg_name(a), b.to_string()],
+            Setr => [d.reg_name(a), String::from("/* ignore */")],
+            Seti => [a.to_string(), String::from("/* ignore */")],
+            Gtir => [a.to_string(), d.reg_name(b)],
+            Gtri => [d.reg_name(a), b.to_string()],
+            Gtrr => [d.reg_name(a), d.reg_name(b)],
+            Eqir => [a.to_string(), d.reg_name(b)],
+            Eqri => [d.reg_name(a), b.to_string()],
+            Eqrr => [d.reg_name(a), d.reg_name(b)],
+        }
+    }
+
+    pub fn decode(input: &str) -> Option<OpCode> {
+        use self::OpCode::*;
+
+        let out = match input {
+            "addr" => Addr,
+            "addi" => Addi,
+            "mulr" => Mulr,
+            "muli" => Muli,
+            "banr" => Banr,
+            "bani" => Bani,
+            "borr" => Borr,
+            "bori" => Bori,
+            "setr" => Setr,
+            "seti" => Seti,
+            "gtir" => Gtir,
+            "gtri" => Gtri,
+            "gtrr" => Gtrr,
+            "eqir" => Eqir,
+            "eqri" => Eqri,
+            "eqrr" => Eqrr,
+            _ => return None,
+        };
+
+        Some(out)
+    }
+
+    fn apply(&self, d: &mut Device, inputs: &[i64; 2], o: i64) -> Result<(), Error> {
+        use self::OpCode::*;
+
+        let [a, b] = *inputs;
+
+        *d.reg(o)? = match *self {
+            Addr => *d.reg(a)? + *d.reg(b)?,
+            Addi => *d.reg(a)? + b,
+            Mulr => *d.reg(a)? * *d.reg(b)?,
+            Muli => *d.reg(a)? * b,
+            Banr => *d.reg(a)? & *d.reg(b)?,
+            Bani => *d.reg(a)? & b,
+            Borr => *d.reg(a)? | *d.reg(b)?,
+            Bori => *d.reg(a)? | b,
+            Setr => *d.reg(a)?,
+            Seti => a,
+            Gtir => {
+                if a > *d.reg(b)? {
+                    1
+                } else {
+                    0
+                }
+            }
+            Gtri => {
+                if *d.reg(a)? > b {
+                    1
+                } else {
+                    0
+                }
+            }
+            Gtrr => {
+                if *d.reg(a)? > *d.reg(b)? {
+                    1
+                } else {
+                    0
+                }
+            }
+            Eqir => {
+                if a == *d.reg(b)? {
+                    1
+                } else {
+                    0
+                }
+            }
+            Eqri => {
+                if *d.reg(a)? == b {
+                    1
+                } else {
+                    0
+                }
+            }
+            Eqrr => {
+                if *d.reg(a)? == *d.reg(b)? {
+                    1
+                } else {
+                    0
+                }
+            }
+        };
+
+        Ok(())
+    }
+}
+
+/// An instruction.
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    op_code: OpCode,
+    inputs: [i64; 2],
+    output: i64,
+}
+
+impl Instruction {
+    pub fn decode(state: &str) -> Option<Instruction> {
+        let mut it = state.split(" ");
+        let op_code = OpCode::decode(it.next()?)?;
+        let mut it = it.flat_map(|d| str::parse(d).ok());
+
+        Some(Instruction {
+            op_code,
+            inputs: [it.next()?, it.next()?],
+            output: it.next()?,
+        })
+    }
+}
+
+/// Convert assembler into a more conveneint (named) format that is easier to disassemble.
+pub fn names<'a>(mut it: impl Iterator<Item = &'a str>) -> Result<(), Error> {
+    let device = Device::load(&mut it)?;
+
+    for (idx, inst) in it.flat_map(Instruction::decode).enumerate() {
+        let [a, b] = inst.op_code.names(&device, inst.inputs);
+        println!(
+            "{:02}: {} {} {} {}",
+            idx,
+            inst.op_code,
+            a,
+            b,
+            device.reg_name(inst.output)
+        );
+    }
+
+    Ok(())
+}
+
+pub fn solve<'a>(
+    visuals: &mut Visuals,
+    mut it: impl Iterator<Item = &'a str>,
+    initial: i64,
+) -> Result<i64, Error> {
+    let mut device = Device::load(&mut it)?;
+
+    *device.reg(0)? = initial;
+
+    let instructions = it.flat_map(Instruction::decode).collect::<Vec<_>>();
+    let mut last = VecDeque::new();
+
+    loop {
+        let ip = *device.ip()?;
+
+        let inst = match instructions.get(ip as usize) {
+            Some(inst) => inst,
+            None => break,
+        };
+
+        let before = device.clone();
+        inst.op_code.apply(&mut device, &inst.inputs, inst.output)?;
+
+        if visuals.is_enabled() {
+            if last.len() == 10 {
+                last.pop_front();
+            }
+
+            last.push_back(inst.clone());
+            visuals.draw(|f| draw(f, &last, &before, &device))?;
+        }
+
+        // NB: the program halts without writing the out-of-range instruction pointer back.
+        let next = *device.ip()? + 1;
+
+        if instructions.get(next as usize).is_none() {
+            break;
+        }
+
+        *device.ip()? = next;
+    }
+
+    visuals.done(format!("Result is {}", device.reg(0)?))?;
+    Ok(*device.reg(0)?)
+}
+
+/// Find the number whose factors the program sums up.
+///
+/// NB: figured out by running the program in interactive mode for a while :)
+/// The program sets up the number and then jumps back to the main loop at instruction 1, at which
+/// point the number is the largest value in any register.
+fn find_target<'a>(mut it: impl Iterator<Item = &'a str>, initial: i64) -> Result<u64, Error> {
+    let mut device = Device::load(&mut it)?;
+    *device.reg(0)? = initial;
+
+    let instructions = it.flat_map(Instruction::decode).collect::<Vec<_>>();
+
+    loop {
+        let ip = *device.ip()?;
+
+        let inst = match instructions.get(ip as usize) {
+            Some(inst) => inst,
+            None => bail!("program terminated before reaching the main loop"),
+        };
+
+        inst.op_code.apply(&mut device, &inst.inputs, inst.output)?;
+        *device.ip()? += 1;
+
+        if *device.ip()? == 1 {
+            break;
+        }
+    }
+
+    match device.registers.iter().cloned().max() {
+        Some(d) if d > 0 => Ok(d as u64),
+        _ => bail!("no target number found"),
+    }
+}
+
+fn part2(d: u64) -> u64 {
+    let mut res = 0u64;
+
+    for f in 1..=d {
+        for b in 1..=d {
+            let c = f * b;
+
+            if c == d {
+                res += f;
+            }
+
+            if c > d {
+                break;
+            }
+        }
+    }
+
+    res
+}
+
+/// Draw the last instructions which were run, and the registers that they changed.
+fn draw(f: &mut Frame, last: &VecDeque<Instruction>, before: &Device, device: &Device) {
+    f.print_styled(0, 2, Style::Underline, "Instructions");
+    f.print_styled(0, 16, Style::Underline, "Registers");
+
+    for (line, inst) in last.iter().enumerate() {
+        let [a, b] = inst.inputs;
+        let text = format!("{} {}, {}, {}", inst.op_code, a, b, inst.output);
+
+        if line + 1 == last.len() {
+            f.print(line + 1, 1, ">");
+            f.print_styled(line + 1, 2, Style::Standout, text);
+        } else {
+            f.print(line + 1, 2, text);
+        }
+    }
+
+    for (line, (a, b)) in before
+        .registers
+        .iter()
+        .zip(device.registers.iter())
+        .enumerate()
+    {
+        let style = if a != b {
+            Style::Standout
+        } else {
+            Style::Normal
+        };
+        f.print(line + 1, 16, format!("{} = ", line));
+        f.print_styled(line + 1, 20, style, b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "#ip 0
+seti 5 0 1
+seti 6 0 2
+addi 0 1 0
+addr 1 2 3
+setr 1 0 0
+seti 8 0 4
+seti 9 0 5
+";
+
+    #[test]
+    fn test_example() -> Result<(), Error> {
+        assert_eq!(solve(&mut Visuals::noop(), EXAMPLE.lines(), 0)?, 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scripted_example() -> Result<(), Error> {
+        use crate::visuals::{Quit, Recording, Script};
+
+        let recording = Recording::default();
+        let script = Script::parse("step 2, quit")?;
+        let mut visuals = Visuals::recorder(&recording).interactive().script(script);
+        let e = solve(&mut visuals, EXAMPLE.lines(), 0).unwrap_err();
+        assert!(e.is::<Quit>());
+
+        let frames = recording.frames();
+        assert_eq!(frames.len(), 3);
+        assert!(frames[2].1.to_string().contains("seti 5, 0, 1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sum_of_factors() {
+        assert_eq!(part2(1), 1);
+        assert_eq!(part2(12), 28);
+        assert_eq!(part2(28), 56);
+    }
+}
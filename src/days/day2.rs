@@ -0,0 +1,64 @@
+use crate::*;
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Vec<char>>;
+    type Part1 = u64;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut ids = Vec::new();
+
+        for line in lines!(input.as_bytes(), String) {
+            ids.push(line?.0.chars().collect::<Vec<_>>());
+        }
+
+        Ok(ids)
+    }
+
+    fn part1(ids: &Self::Input) -> Result<u64, Error> {
+        let mut counts = HashMap::<_, u64>::new();
+
+        for chars in ids {
+            let mut m = HashMap::new();
+
+            for c in chars.iter().cloned() {
+                *m.entry(c).or_default() += 1;
+            }
+
+            // Collect and de-dup all counts.
+            for v in m.values().cloned().collect::<HashSet<u64>>() {
+                *counts.entry(v).or_default() += 1;
+            }
+        }
+
+        Ok([2, 3].iter().flat_map(|k| counts.get(k)).product::<u64>())
+    }
+
+    fn part2(ids: &Self::Input) -> Result<String, Error> {
+        let set = ids.iter().cloned().collect::<BTreeSet<_>>();
+        part2(&set).ok_or_else(|| format_err!("no ids differ by exactly one character"))
+    }
+}
+
+/// Calculate part two.
+fn part2(set: &BTreeSet<Vec<char>>) -> Option<String> {
+    let mut it = set.iter().peekable();
+
+    while let (Some(a), Some(b)) = (it.next(), it.peek()) {
+        let s = a
+            .iter()
+            .zip(*b)
+            .filter(|(a, b)| a == b)
+            .map(|(a, _)| *a)
+            .collect::<Vec<_>>();
+
+        // should only differ by one character.
+        if s.len() == a.len() - 1 {
+            return Some(s.into_iter().collect());
+        }
+    }
+
+    None
+}
@@ -0,0 +1,389 @@
+use crate::export::Palette;
+use crate::gen::Rng;
+use crate::visuals::Visuals;
+use crate::*;
+
+use std::fmt;
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Expr;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let (expr,) = scan!(line = 1, input.trim(), "^{}$", String)?;
+        Expr::parse(&expr)
+    }
+
+    fn part1(expr: &Self::Input) -> Result<usize, Error> {
+        find_furthest(&expr.walk()?).ok_or_else(|| format_err!("no rooms"))
+    }
+
+    fn part2(expr: &Self::Input) -> Result<usize, Error> {
+        Ok(count_by_limit(&expr.walk()?, 1000))
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Expr {
+    items: Vec<Item>,
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for item in &self.items {
+            write!(fmt, "{}", item)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Item {
+    Group(Vec<Expr>),
+    Route(Vec<Dir>),
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Item::Group(ref entries) => {
+                let mut it = entries.iter().peekable();
+
+                write!(fmt, "(")?;
+
+                while let Some(g) = it.next() {
+                    fmt::Display::fmt(g, fmt)?;
+
+                    if it.peek().is_some() {
+                        write!(fmt, "|")?;
+                    }
+                }
+
+                write!(fmt, ")")?;
+            }
+            Item::Route(ref route) => {
+                for d in route {
+                    d.fmt(fmt)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Expr {
+    pub fn parse(input: &str) -> Result<Expr, Error> {
+        use std::mem;
+
+        let mut route = Vec::new();
+        let mut items = Vec::new();
+
+        let mut it = input.chars();
+
+        while let Some(c) = it.next() {
+            match c {
+                '^' | '$' => {}
+                '(' => {
+                    if !route.is_empty() {
+                        items.push(Item::Route(mem::take(&mut route)));
+                    }
+
+                    items.push(Item::Group(Self::parse_group(&mut it)?));
+                }
+                'N' => route.push(Dir::North),
+                'E' => route.push(Dir::East),
+                'S' => route.push(Dir::South),
+                'W' => route.push(Dir::West),
+                c => {
+                    bail!("bad character in input: {}", c);
+                }
+            }
+        }
+
+        if !route.is_empty() {
+            items.push(Item::Route(mem::take(&mut route)));
+        }
+
+        Ok(Expr { items })
+    }
+
+    fn parse_group(it: &mut impl Iterator<Item = char>) -> Result<Vec<Expr>, Error> {
+        use std::mem;
+
+        let mut route = Vec::new();
+        let mut items = Vec::new();
+        let mut entries = Vec::new();
+
+        while let Some(c) = it.next() {
+            match c {
+                '|' => {
+                    if !route.is_empty() {
+                        items.push(Item::Route(mem::take(&mut route)));
+                    }
+
+                    entries.push(Expr {
+                        items: mem::take(&mut items),
+                    })
+                }
+                '(' => {
+                    if !route.is_empty() {
+                        items.push(Item::Route(mem::take(&mut route)));
+                    }
+
+                    items.push(Item::Group(Self::parse_group(it)?));
+                }
+                'N' => route.push(Dir::North),
+                'E' => route.push(Dir::East),
+                'S' => route.push(Dir::South),
+                'W' => route.push(Dir::West),
+                ')' => {
+                    if !route.is_empty() {
+                        items.push(Item::Route(route));
+                    }
+
+                    entries.push(Expr { items });
+
+                    return Ok(entries);
+                }
+                c => {
+                    bail!("bad character in input: {}", c);
+                }
+            }
+        }
+
+        bail!("missing closing parenthesis")
+    }
+
+    pub fn walk(&self) -> Result<HashMap<Pos, HashSet<Dir>>, Error> {
+        let mut doors = HashMap::<Pos, HashSet<Dir>>::new();
+        self.walk_inner(Pos::default(), &mut doors)?;
+        Ok(doors)
+    }
+
+    pub fn walk_inner(
+        &self,
+        pos: Pos,
+        doors: &mut HashMap<Pos, HashSet<Dir>>,
+    ) -> Result<HashSet<Pos>, Error> {
+        let mut queue = VecDeque::new();
+
+        if let Some((item, items)) = self.items.split_first() {
+            queue.push_back((pos, Some(item), items));
+        }
+
+        let mut positions = HashSet::new();
+
+        while let Some((pos, item, items)) = queue.pop_front() {
+            let item = match item {
+                None => {
+                    positions.insert(pos);
+                    continue;
+                }
+                Some(item) => item,
+            };
+
+            match *item {
+                Item::Route(ref route) => {
+                    let mut pos = pos;
+
+                    for d in route.iter().cloned() {
+                        let n = pos.step(d);
+
+                        doors.entry(pos).or_default().insert(d);
+                        doors.entry(n).or_default().insert(d.reflect());
+
+                        pos = n;
+                    }
+
+                    if let Some((item, items)) = items.split_first() {
+                        queue.push_back((pos, Some(item), items));
+                    } else {
+                        queue.push_back((pos, None, items));
+                    }
+                }
+                Item::Group(ref group) => {
+                    let mut positions = HashSet::new();
+
+                    for expr in group {
+                        positions.extend(expr.walk_inner(pos, doors)?);
+                    }
+
+                    for pos in positions {
+                        if let Some((item, items)) = items.split_first() {
+                            queue.push_back((pos, Some(item), items));
+                        } else {
+                            queue.push_back((pos, None, items));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(positions)
+    }
+}
+
+/// Just for fun function to render a set of doors.
+pub fn render(doors: &HashMap<Pos, HashSet<Dir>>, visuals: &mut Visuals) -> Result<(), Error> {
+    let mut grid = HashMap::new();
+
+    for (pos, dirs) in doors {
+        let room = *pos * 2;
+        grid.insert(room, '.');
+
+        for d in dirs.iter().cloned() {
+            let door = match d {
+                Dir::North | Dir::South => '-',
+                Dir::East | Dir::West => '|',
+            };
+
+            if let Some(existing) = grid.insert(room.step(d), door) {
+                if existing != door {
+                    bail!("existing `{}` != inserted `{}`", existing, door);
+                }
+            }
+        }
+    }
+
+    let bounds = grid
+        .keys()
+        .map(|p| (p.x, p.y))
+        .collect::<Bounds2<i64>>()
+        .grow(1);
+    let ((x0, y0), _) = bounds.get().ok_or_else(|| format_err!("no doors"))?;
+
+    visuals.draw(|f| {
+        for (x, y) in bounds.iter() {
+            let c = grid.get(&Pos::new(x, y)).cloned().unwrap_or('#');
+            f.print((y - y0) as usize, (x - x0) as usize, c);
+        }
+    })?;
+
+    visuals.done("")
+}
+
+/// Colors to use when exporting the rendered doors.
+pub fn palette() -> Palette {
+    Palette::new([0x20, 0x20, 0x20])
+        .color('#', [0x60, 0x60, 0x60])
+        .color('.', [0xf0, 0xe0, 0xc0])
+        .color('-', [0xc0, 0x80, 0x40])
+        .color('|', [0xc0, 0x80, 0x40])
+}
+
+/// Find the distance to every room, counted in doors passed through from the starting room.
+pub fn distances(doors: &HashMap<Pos, HashSet<Dir>>) -> search::Paths<Pos, usize> {
+    search::bfs(Some(Pos::default()), |pos| {
+        let pos = *pos;
+
+        doors
+            .get(&pos)
+            .into_iter()
+            .flat_map(|d| d.iter())
+            .map(move |dir| pos.step(*dir))
+    })
+}
+
+pub fn find_furthest(doors: &HashMap<Pos, HashSet<Dir>>) -> Option<usize> {
+    distances(doors).iter().map(|(_, d)| d).max()
+}
+
+pub fn count_by_limit(doors: &HashMap<Pos, HashSet<Dir>>, limit: usize) -> usize {
+    distances(doors)
+        .iter()
+        .filter(move |(_, d)| *d >= limit)
+        .count()
+}
+
+/// Generate a route with `size` top-level parts, each a run of directions or a group.
+///
+/// Like the puzzle input, groups which are followed by more of the route are detours that return
+/// to where they started. Only groups at the end of a route branch off into separate routes, since
+/// the number of positions to walk from would otherwise grow with every group.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut out = String::from("^");
+    sequence(rng, size.max(1), 0, &mut out);
+    out.push_str("$\n");
+    return out;
+
+    fn sequence(rng: &mut Rng, parts: usize, depth: usize, out: &mut String) {
+        for part in 0..parts {
+            if depth >= 3 || rng.chance(0.6) {
+                run(rng, out);
+                continue;
+            }
+
+            let last = part + 1 == parts;
+            out.push('(');
+
+            for option in 0..rng.range(2..4) {
+                if option > 0 {
+                    out.push('|');
+                }
+
+                if last {
+                    let parts = rng.below(3) + 1;
+                    sequence(rng, parts, depth + 1, out);
+                } else {
+                    let start = out.len();
+                    run(rng, out);
+                    let back = out[start..].chars().rev().map(reflect).collect::<String>();
+                    out.push_str(&back);
+                }
+            }
+
+            if !last {
+                out.push('|');
+            }
+
+            out.push(')');
+        }
+    }
+
+    fn run(rng: &mut Rng, out: &mut String) {
+        for _ in 0..rng.range(1..6) {
+            out.push(*rng.choose(&['N', 'E', 'W', 'S']).expect("directions"));
+        }
+    }
+
+    fn reflect(c: char) -> char {
+        match c {
+            'N' => 'S',
+            'E' => 'W',
+            'S' => 'N',
+            'W' => 'E',
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() -> Result<(), Error> {
+        let examples = [
+            ("^WNE$", 3),
+            ("^ENWWW(NEEE|SSE(EE|N))$", 10),
+            ("^ENNWSWW(NEWS|)SSSEEN(WNSE|)EEESSSWWWNNE$", 18),
+            (input_str!("day20a.txt"), 23),
+            (input_str!("day20b.txt"), 31),
+        ];
+
+        for &(input, expected) in &examples {
+            assert_eq!(Day20::part1(&Day20::parse(input)?)?, expected);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use crate::gen::Rng;
+use crate::*;
+
+pub struct Day3;
+
+/// A claim, with an id, an offset and a size.
+pub type Claim = (u32, (u32, u32), (u32, u32));
+
+impl Solution for Day3 {
+    type Input = Vec<Claim>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut claims = Vec::new();
+
+        for claim in scan!(input, "#{} @ {},{}: {}x{}", u32, u32, u32, u32, u32) {
+            let (id, x, y, w, h) = claim?;
+            claims.push((id, (x, y), (w, h)));
+        }
+
+        Ok(claims)
+    }
+
+    fn part1(claims: &Self::Input) -> Result<u32, Error> {
+        Ok(solve(claims).0)
+    }
+
+    fn part2(claims: &Self::Input) -> Result<u32, Error> {
+        let nonoverlapping = solve(claims).1;
+
+        match nonoverlapping.as_slice() {
+            [id] => Ok(*id),
+            other => bail!(
+                "expected exactly one non-overlapping claim, but got: {:?}",
+                other
+            ),
+        }
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+/// Count the number of square inches claimed more than once, and find all non-overlapping claims.
+fn solve(claims: &[Claim]) -> (u32, Vec<u32>) {
+    let mut duplicates = 0;
+
+    // map out who owns each claim.
+    let mut claimed_by = HashMap::<_, Vec<u32>>::new();
+    let mut nonoverlapping = HashSet::new();
+
+    for (id, b, c) in claims {
+        nonoverlapping.insert(*id);
+
+        for x in b.0..b.0.saturating_add(c.0) {
+            for y in b.1..b.1.saturating_add(c.1) {
+                let m = claimed_by.entry((x, y)).or_default();
+                m.push(*id);
+
+                if m.len() == 2 {
+                    duplicates += 1;
+                }
+
+                if m.len() > 1 {
+                    for remove in m {
+                        nonoverlapping.remove(remove);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut nonoverlapping = nonoverlapping.into_iter().collect::<Vec<_>>();
+    nonoverlapping.sort();
+    (duplicates, nonoverlapping)
+}
+
+/// Generate `size` claims, but at least 3, where exactly one doesn't overlap any other claim.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut claims = Vec::new();
+    let mut last = None::<(i64, i64, i64, i64)>;
+
+    // NB: every claim overlaps the one before it, so at least two are needed for them to overlap
+    // anything at all.
+    for _ in 1..size.max(3) {
+        let (w, h) = (rng.range(1..10), rng.range(1..10));
+
+        let (x, y) = match last {
+            Some((lx, ly, lw, lh)) => (
+                (lx + rng.range(0..lw) - rng.range(0..w)).max(0),
+                (ly + rng.range(0..lh) - rng.range(0..h)).max(0),
+            ),
+            None => (rng.range(0..50), rng.range(0..50)),
+        };
+
+        claims.push((x, y, w, h));
+        last = Some((x, y, w, h));
+    }
+
+    let right = claims.iter().map(|c| c.0 + c.2).max().unwrap_or_default();
+    let alone = (
+        right + rng.range(1..10),
+        rng.range(0..50),
+        rng.range(1..10),
+        rng.range(1..10),
+    );
+    let at = rng.below(claims.len() + 1);
+    claims.insert(at, alone);
+
+    let mut out = String::new();
+
+    for (id, (x, y, w, h)) in claims.into_iter().enumerate() {
+        out.push_str(&format!("#{} @ {},{}: {}x{}\n", id + 1, x, y, w, h));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_small() -> Result<(), Error> {
+        for size in 0..=3 {
+            for seed in 0..20 {
+                let input = generate(&mut Rng::new(seed), size);
+                let claims = Day3::parse(&input)?;
+                let id = Day3::part2(&claims)?;
+                assert!(claims.iter().any(|c| c.0 == id), "no claim #{}", id);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+use crate::gen::Rng;
+use crate::*;
+
+pub struct Day4;
+
+/// A timestamped record from the guard log.
+pub type Record = (chrono::NaiveDateTime, String);
+
+/// A line in the guard log, with the date and time followed by what happened.
+const TEMPLATE: &str = "[{}-{}-{} {}:{}] {}";
+/// A line in the guard log where a guard begins their shift.
+const SHIFT: &str = "[{}] Guard #{} begins shift";
+
+impl Solution for Day4 {
+    type Input = Vec<Record>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut records = Vec::new();
+
+        for record in scan!(input, TEMPLATE, i32, u32, u32, u32, u32, String) {
+            let (year, month, day, hour, minute, rest) = record?;
+
+            let date = chrono::NaiveDate::from_ymd_opt(year, month, day)
+                .and_then(|d| d.and_hms_opt(hour, minute, 0))
+                .ok_or_else(|| {
+                    format_err!("bad date: {}-{}-{} {}:{}", year, month, day, hour, minute)
+                })?;
+
+            records.push((date, rest));
+        }
+
+        records.sort_by_key(|a| a.0);
+        Ok(records)
+    }
+
+    fn validate(input: &str) -> Vec<ParseError> {
+        let mut errors = Vec::new();
+        let mut records = Vec::new();
+
+        for (n, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (year, month, day, hour, minute, rest) = match scan!(
+                line = n + 1,
+                line,
+                TEMPLATE,
+                i32,
+                u32,
+                u32,
+                u32,
+                u32,
+                String
+            ) {
+                Ok(record) => record,
+                Err(e) => {
+                    errors.push(ParseError::from_error(n + 1, line, e));
+                    continue;
+                }
+            };
+
+            let stamp = 1..line.find(']').unwrap_or_default();
+
+            let date = match chrono::NaiveDate::from_ymd_opt(year, month, day)
+                .and_then(|d| d.and_hms_opt(hour, minute, 0))
+            {
+                Some(date) => date,
+                None => {
+                    errors.push(ParseError::new(n + 1, line, stamp, "bad date"));
+                    continue;
+                }
+            };
+
+            let kind = match rest.as_str() {
+                "falls asleep" => Kind::Sleep,
+                "wakes up" => Kind::Wake,
+                _ if rest.ends_with("begins shift") => {
+                    if let Err(e) = scan!(line = n + 1, line, SHIFT, Skip, u32) {
+                        errors.push(ParseError::from_error(n + 1, line, e));
+                        continue;
+                    }
+
+                    Kind::Shift
+                }
+                _ => {
+                    let span = stamp.end + 2..line.len();
+                    let message =
+                        "expected `Guard #<id> begins shift`, `falls asleep` or `wakes up`";
+                    errors.push(ParseError::new(n + 1, line, span, message));
+                    continue;
+                }
+            };
+
+            records.push((date, kind, n + 1, line));
+        }
+
+        // NB: records are out of order in the input, so check that guards fall asleep and wake up
+        // in turn once they're sorted.
+        records.sort_by_key(|r| r.0);
+
+        let mut shift = false;
+        let mut asleep = false;
+
+        for (_, kind, n, line) in records {
+            let message = match kind {
+                Kind::Shift if asleep => {
+                    asleep = false;
+                    "shift begins while the previous guard is still asleep"
+                }
+                Kind::Shift => {
+                    shift = true;
+                    continue;
+                }
+                Kind::Sleep if !shift => "guard falls asleep before any shift has begun",
+                Kind::Sleep if asleep => "guard falls asleep while already asleep",
+                Kind::Wake if !asleep => "guard wakes up without having fallen asleep",
+                Kind::Sleep | Kind::Wake => {
+                    asleep = kind == Kind::Sleep;
+                    continue;
+                }
+            };
+
+            errors.push(ParseError::new(n, line, 0..line.len(), message));
+        }
+
+        errors
+    }
+
+    fn part1(records: &Self::Input) -> Result<u32, Error> {
+        let Schedule {
+            minutes_asleep,
+            mut guard_asleep_at,
+            ..
+        } = Schedule::build(records)?;
+
+        let max = minutes_asleep
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1))
+            .ok_or_else(|| format_err!("no solution found"))?;
+
+        let pair = guard_asleep_at
+            .remove(&max.0)
+            .unwrap_or_default()
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1))
+            .ok_or_else(|| format_err!("no solution found"))?;
+
+        Ok(pair.0 * max.0)
+    }
+
+    fn part2(records: &Self::Input) -> Result<u32, Error> {
+        let Schedule { minute_asleep, .. } = Schedule::build(records)?;
+
+        let mut sleep_min = None;
+
+        for (ts, guards) in minute_asleep {
+            if let Some((guard, times)) = guards.into_iter().max_by(|a, b| a.1.cmp(&b.1)) {
+                sleep_min = match sleep_min {
+                    Some((ts, guard, max)) if max > times => Some((ts, guard, max)),
+                    Some(_) | None => Some((ts, guard, times)),
+                };
+            }
+        }
+
+        let sleep_min = sleep_min.ok_or_else(|| format_err!("no result found"))?;
+        Ok(sleep_min.0 * sleep_min.1)
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+/// The kind of a record, used while validating the log.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Shift,
+    Sleep,
+    Wake,
+}
+
+/// When each guard is asleep.
+struct Schedule {
+    minutes_asleep: HashMap<u32, u32>,
+    guard_asleep_at: HashMap<u32, HashMap<u32, u32>>,
+    minute_asleep: HashMap<u32, HashMap<u32, u32>>,
+}
+
+impl Schedule {
+    fn build(records: &[Record]) -> Result<Schedule, Error> {
+        let mut current = 0u32;
+        let mut asleep = None;
+
+        let mut minutes_asleep = HashMap::<_, u32>::new();
+        let mut guard_asleep_at = HashMap::<_, HashMap<u32, u32>>::new();
+        let mut minute_asleep = HashMap::<_, HashMap<u32, u32>>::new();
+
+        for (date, rest) in records {
+            if rest.ends_with("begins shift") {
+                let guard_id = rest
+                    .split(" ")
+                    .nth(1)
+                    .ok_or_else(|| format_err!("no guard id"))?
+                    .trim_matches('#');
+
+                current = str::parse(guard_id)?;
+                asleep = None;
+                continue;
+            }
+
+            match rest.as_str() {
+                "falls asleep" => {
+                    asleep = Some(*date);
+                }
+                "wakes up" => {
+                    let asleep = asleep.as_ref().ok_or_else(|| {
+                        format_err!("guard #{} woke up without sleeping: {}", current, date)
+                    })?;
+                    let count = date.signed_duration_since(*asleep).num_minutes() as u32;
+
+                    *minutes_asleep.entry(current).or_default() += count;
+
+                    for m in (asleep.minute()..).take(count as usize) {
+                        *guard_asleep_at
+                            .entry(current)
+                            .or_default()
+                            .entry(m)
+                            .or_default() += 1;
+
+                        *minute_asleep
+                            .entry(m)
+                            .or_default()
+                            .entry(current)
+                            .or_default() += 1;
+                    }
+                }
+                other => {
+                    bail!("unsupported record: {}", other);
+                }
+            }
+        }
+
+        Ok(Schedule {
+            minutes_asleep,
+            guard_asleep_at,
+            minute_asleep,
+        })
+    }
+}
+
+/// Generate guard logs for `size` shifts, in random order.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let guards = (0..size / 3 + 1)
+        .map(|_| rng.range(1..3000))
+        .collect::<Vec<_>>();
+
+    let start = chrono::NaiveDate::from_ymd_opt(1518, 3, 1).expect("valid date");
+    let mut records = Vec::new();
+
+    for n in 0..size.max(1) {
+        let day = start + chrono::Duration::days(n as i64);
+        let guard = *rng.choose(&guards).expect("guards");
+
+        let begins = if rng.chance(0.5) {
+            let before = day - chrono::Duration::days(1);
+            before.and_hms_opt(23, rng.range(45..60) as u32, 0)
+        } else {
+            day.and_hms_opt(0, rng.range(0..5) as u32, 0)
+        };
+
+        let begins = begins.expect("valid time");
+        records.push((begins, format!("Guard #{} begins shift", guard)));
+
+        // NB: every shift has at least one nap, so that some guard is asleep.
+        let mut minute = rng.range(5..20);
+
+        while minute < 58 {
+            let wakes = rng.range(minute + 1..60);
+            let asleep = day.and_hms_opt(0, minute as u32, 0).expect("valid time");
+            let awake = day.and_hms_opt(0, wakes as u32, 0).expect("valid time");
+            records.push((asleep, String::from("falls asleep")));
+            records.push((awake, String::from("wakes up")));
+
+            if wakes >= 58 || rng.chance(0.4) {
+                break;
+            }
+
+            minute = rng.range(wakes + 1..60);
+        }
+    }
+
+    rng.shuffle(&mut records);
+
+    let mut out = String::new();
+
+    for (date, text) in records {
+        out.push_str(&format!("[{}] {}\n", date.format("%Y-%m-%d %H:%M"), text));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let input = "\
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] wakes up
+[1518-13-01 00:00] Guard #10 begins shift
+[1518-11-02 00:00] Guard #x begins shift
+[1518-11-02 00:10] dozes off
+";
+
+        let errors = Day4::validate(input)
+            .into_iter()
+            .map(|e| (e.line(), e.columns(), e.message().to_string()))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            (5, 2..18, String::from("bad date")),
+            (
+                6,
+                27..28,
+                String::from("bad `u32`: bad value `x`: invalid digit found in string"),
+            ),
+            (
+                7,
+                20..29,
+                String::from("expected `Guard #<id> begins shift`, `falls asleep` or `wakes up`"),
+            ),
+            (
+                4,
+                1..28,
+                String::from("guard wakes up without having fallen asleep"),
+            ),
+        ];
+
+        assert_eq!(errors, expected);
+    }
+
+    #[test]
+    fn test_validate_asleep_at_shift() {
+        let input = "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-02 00:00] Guard #99 begins shift
+[1518-11-02 00:10] falls asleep
+[1518-11-02 00:20] wakes up
+";
+
+        let errors = Day4::validate(input)
+            .into_iter()
+            .map(|e| (e.line(), e.message().to_string()))
+            .collect::<Vec<_>>();
+
+        let expected = vec![(
+            3,
+            String::from("shift begins while the previous guard is still asleep"),
+        )];
+
+        assert_eq!(errors, expected);
+    }
+}
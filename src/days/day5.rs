@@ -0,0 +1,160 @@
+use crate::*;
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        Ok(input.trim().to_string())
+    }
+
+    fn part1(polymer: &Self::Input) -> Result<usize, Error> {
+        Ok(clever(polymer))
+    }
+
+    fn part2(polymer: &Self::Input) -> Result<usize, Error> {
+        test_with_removal(polymer, clever).ok_or_else(|| format_err!("polymer is empty"))
+    }
+
+    fn variants() -> Vec<Variant<Self::Input>> {
+        vec![
+            Variant::new(Part::One, "naive", |polymer: &String| Ok(naive(polymer))),
+            Variant::new(Part::Two, "naive", |polymer: &String| {
+                test_with_removal(polymer, naive).ok_or_else(|| format_err!("polymer is empty"))
+            }),
+        ]
+    }
+}
+
+fn is_polar(a: char, b: char) -> bool {
+    b.is_uppercase() && b.to_lowercase().next() == Some(a)
+        || a.is_uppercase() && a.to_lowercase().next() == Some(b)
+}
+
+/// Naive implementation. Just reduce two inputs.
+pub fn naive(input: &str) -> usize {
+    let mut chain = input.trim().chars().collect::<Vec<_>>();
+
+    loop {
+        let mut it = chain.iter().cloned().peekable();
+
+        let mut out = Vec::new();
+
+        while let Some(a) = it.next() {
+            let b = it.peek().cloned();
+
+            if let Some(b) = b {
+                if is_polar(a, b) {
+                    it.next();
+                    continue;
+                }
+            }
+
+            out.push(a);
+        }
+
+        if out.len() == chain.len() {
+            return chain.len();
+        }
+
+        chain = out;
+    }
+}
+
+/// Clever implementation.
+pub fn clever(input: &str) -> usize {
+    let mut stack = Vec::new();
+
+    for c in input.trim().chars() {
+        match stack.last().cloned() {
+            Some(n) if is_polar(n, c) => {
+                stack.pop();
+            }
+            _ => {
+                stack.push(c);
+            }
+        }
+    }
+
+    stack.len()
+}
+
+/// Test all against the given input, after removing each unique character from the input.
+pub fn test_with_removal(input: &str, f: impl Fn(&str) -> usize) -> Option<usize> {
+    let chars = input
+        .trim()
+        .chars()
+        .flat_map(|c| c.to_lowercase())
+        .collect::<HashSet<_>>();
+
+    let mut max = None;
+
+    for remove in chars {
+        let s = input
+            .chars()
+            .filter(|c| !c.eq_ignore_ascii_case(&remove))
+            .collect::<String>();
+        let l = f(&s);
+
+        max = match max {
+            Some(m) if m > l => Some(l),
+            None => Some(l),
+            Some(m) => Some(m),
+        };
+    }
+
+    max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "dabAcCaCBAcCcaDA";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(naive(EXAMPLE), 10);
+        assert_eq!(clever(EXAMPLE), 10);
+    }
+
+    #[test]
+    fn test_example_with_removal() {
+        assert_eq!(test_with_removal(EXAMPLE, naive), Some(4));
+        assert_eq!(test_with_removal(EXAMPLE, clever), Some(4));
+    }
+
+    #[test]
+    fn test_naive_and_clever_agree() {
+        // NB: a small alphabet, so that reacting units are common.
+        gen::check(
+            500,
+            |rng| {
+                (0..rng.below(60))
+                    .map(|_| *rng.choose(&['a', 'A', 'b', 'B', 'c', 'C']).expect("units"))
+                    .collect::<String>()
+            },
+            |polymer| {
+                let (a, b) = (naive(polymer), clever(polymer));
+
+                if a != b {
+                    bail!("naive reduced to {} units, but clever to {}", a, b);
+                }
+
+                let (a, b) = (
+                    test_with_removal(polymer, naive),
+                    test_with_removal(polymer, clever),
+                );
+
+                if a != b {
+                    bail!("with removal, naive gave {:?} but clever {:?}", a, b);
+                }
+
+                Ok(())
+            },
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use crate::*;
+
+use std::fmt;
+
+pub struct Day6;
+
+/// The bounds of the coordinates, and the coordinates.
+pub type Coords = (Bounds2<i64>, Vec<Pos>);
+
+impl Solution for Day6 {
+    type Input = Coords;
+    type Part1 = u32;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut coords = Vec::new();
+
+        for line in lines!(input.as_bytes(), Trim<i64>, i64) {
+            let (Trim(x), y) = line?;
+            coords.push(Pos::new(x, y));
+        }
+
+        let bounds = coords.iter().map(|&c| c.into()).collect::<Bounds2<_>>();
+
+        if bounds.is_empty() {
+            bail!("no coordinates");
+        }
+
+        Ok((bounds, coords))
+    }
+
+    fn part1((bounds, coords): &Self::Input) -> Result<u32, Error> {
+        part1(bounds, coords).ok_or_else(|| format_err!("no finite area"))
+    }
+
+    fn part2((bounds, coords): &Self::Input) -> Result<usize, Error> {
+        Ok(part2(bounds, |c| part2_constraint(c, coords, 10000)))
+    }
+}
+
+type Origin = usize;
+
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    Conflicted(u32),
+    Distance(Origin, u32),
+}
+
+impl fmt::Display for Node {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Node::Conflicted(_) => "..".fmt(fmt),
+            Node::Distance(o, _) => write!(fmt, "{:02}", o),
+        }
+    }
+}
+
+/// Traverse and mark entire space of coordinates.
+///
+/// Only the arena bounds are traversed, since areas which reach outside of them are infinite.
+fn part1(bounds: &Bounds2<i64>, coords: &[Pos]) -> Option<u32> {
+    let mut infinites = HashSet::new();
+    let mut m = HashMap::new();
+
+    for (i, c) in coords.iter().cloned().enumerate() {
+        if !is_finite(c, coords) {
+            infinites.insert(i);
+        }
+
+        let paths = search::bfs(Some(c), |c| {
+            c.neighbours4().filter(|n| bounds.contains((*n).into()))
+        });
+
+        for (c, d) in paths.iter() {
+            let d = d as u32;
+
+            match m.entry(*c) {
+                hash_map::Entry::Vacant(e) => {
+                    e.insert(Node::Distance(i, d));
+                }
+                hash_map::Entry::Occupied(mut e) => {
+                    // test existing node.
+                    match *e.get() {
+                        Node::Distance(_, p) | Node::Conflicted(p) if p > d => {
+                            e.insert(Node::Distance(i, d));
+                        }
+                        Node::Distance(other, p) if p == d && other != i => {
+                            e.insert(Node::Conflicted(d));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    let mut results = HashMap::<usize, u32>::new();
+
+    for c in bounds.iter().map(Pos::from) {
+        if let Some(Node::Distance(o, _)) = m.get(&c).cloned() {
+            if !infinites.contains(&o) {
+                *results.entry(o).or_default() += 1;
+            }
+        }
+    }
+
+    return results
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1))
+        .map(|n| n.1);
+
+    /// Test if a coord is constrained in all directions.
+    ///
+    /// A coord is constrained if any other coordinate would reach an intersection faster than the
+    /// coordinate being tested in all directions.
+    fn is_finite(c: Pos, coords: &[Pos]) -> bool {
+        // various directions we might be constrained.
+        let mut c_px = false;
+        let mut c_nx = false;
+        let mut c_py = false;
+        let mut c_ny = false;
+
+        for t in coords.iter().cloned() {
+            if t == c {
+                continue;
+            }
+
+            let dx = (t.x - c.x).unsigned_abs();
+            let dy = (t.y - c.y).unsigned_abs();
+
+            if dx >= dy {
+                if t.x > c.x {
+                    c_px = true;
+                } else {
+                    c_nx = true;
+                }
+            }
+
+            if dy >= dx {
+                if t.y > c.y {
+                    c_py = true;
+                } else {
+                    c_ny = true;
+                }
+            }
+        }
+
+        c_px && c_nx && c_py && c_ny
+    }
+}
+
+/// Find all coordinates that satisfy the given constraints.
+///
+/// We know that if one coordinate exists, it has to be within the bounds, so start looking there.
+fn part2(bounds: &Bounds2<i64>, constraint: impl Fn(Pos) -> bool) -> usize {
+    let start = bounds.iter().map(Pos::from).find(|c| constraint(*c));
+    search::bfs(start, |c| c.neighbours4().filter(|c| constraint(*c))).len()
+}
+
+fn part2_constraint(c: Pos, coords: &[Pos], limit: i64) -> bool {
+    coords.iter().map(|o| c.manhattan(*o)).sum::<i64>() < limit
+}
@@ -0,0 +1,480 @@
+use crate::events::{self, Events, Value};
+use crate::gen::Rng;
+use crate::*;
+
+pub struct Day7;
+
+/// A line stating that one step depends on another.
+const TEMPLATE: &str = "Step {} must be finished before step {} can begin.";
+
+impl Solution for Day7 {
+    type Input = HashMap<char, Vec<char>>;
+    type Part1 = String;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut deps = HashMap::<char, Vec<char>>::new();
+
+        for line in scan!(input, TEMPLATE, char, char) {
+            let (before, after) = line?;
+            deps.entry(after).or_default().push(before);
+            deps.entry(before).or_default();
+        }
+
+        Ok(deps)
+    }
+
+    fn validate(input: &str) -> Vec<ParseError> {
+        let mut errors = Vec::new();
+        let mut edges = Vec::new();
+
+        for (n, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let captures = match scan_captures(TEMPLATE, 2, n + 1, line) {
+                Ok(captures) => captures,
+                Err(e) => {
+                    errors.push(ParseError::from_error(n + 1, line, e));
+                    continue;
+                }
+            };
+
+            let mut steps = Vec::new();
+
+            for (span, capture) in captures {
+                match parse_capture::<char>("char", n + 1, line, span.clone(), capture) {
+                    Ok(c) if c.is_ascii_uppercase() => steps.push(c),
+                    Ok(c) => {
+                        let message = format!("step `{}` is not an uppercase letter", c);
+                        errors.push(ParseError::new(n + 1, line, span, message));
+                    }
+                    Err(e) => errors.push(e),
+                }
+            }
+
+            if let [before, after] = steps[..] {
+                edges.push((before, after, n + 1, line));
+            }
+        }
+
+        // NB: steps in the same strongly connected component can each be reached from the other, so
+        // an edge within a component is part of a cycle and could never be assembled.
+        let component = components(edges.iter().map(|&(before, after, _, _)| (before, after)));
+
+        for &(before, after, n, line) in &edges {
+            if component.get(&before) != component.get(&after) {
+                continue;
+            }
+
+            let message = if before == after {
+                format!("step {} depends on itself", before)
+            } else {
+                format!(
+                    "steps {} and {} are part of a dependency cycle",
+                    before, after
+                )
+            };
+
+            errors.push(ParseError::new(n, line, 0..line.len(), message));
+        }
+
+        errors
+    }
+
+    fn part1(deps: &Self::Input) -> Result<String, Error> {
+        Ok(part1(deps))
+    }
+
+    fn part2(deps: &Self::Input) -> Result<u32, Error> {
+        part2(deps, 60, 5, &mut Events::noop())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+/// Find the strongly connected component of every step with Tarjan's algorithm, given the edges
+/// from each step to the steps depending on it.
+fn components(edges: impl IntoIterator<Item = (char, char)>) -> HashMap<char, usize> {
+    let mut graph = BTreeMap::<char, Vec<char>>::new();
+
+    for (before, after) in edges {
+        graph.entry(before).or_default().push(after);
+        graph.entry(after).or_default();
+    }
+
+    let mut tarjan = Tarjan {
+        graph: &graph,
+        index: HashMap::new(),
+        low: HashMap::new(),
+        stack: Vec::new(),
+        component: HashMap::new(),
+        components: 0,
+    };
+
+    for step in graph.keys().cloned() {
+        if !tarjan.index.contains_key(&step) {
+            tarjan.visit(step);
+        }
+    }
+
+    return tarjan.component;
+
+    struct Tarjan<'a> {
+        graph: &'a BTreeMap<char, Vec<char>>,
+        /// The order in which each step was first visited.
+        index: HashMap<char, usize>,
+        /// The earliest visited step on the stack which each step can reach.
+        low: HashMap<char, usize>,
+        /// Visited steps which aren't assigned to a component yet.
+        stack: Vec<char>,
+        component: HashMap<char, usize>,
+        components: usize,
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, step: char) {
+            let index = self.index.len();
+            self.index.insert(step, index);
+            self.low.insert(step, index);
+            self.stack.push(step);
+
+            for next in self.graph[&step].iter().cloned() {
+                let low = match self.index.get(&next) {
+                    None => {
+                        self.visit(next);
+                        self.low[&next]
+                    }
+                    Some(index) if !self.component.contains_key(&next) => *index,
+                    Some(_) => continue,
+                };
+
+                let current = self.low[&step];
+                self.low.insert(step, current.min(low));
+            }
+
+            if self.low[&step] != index {
+                return;
+            }
+
+            while let Some(member) = self.stack.pop() {
+                self.component.insert(member, self.components);
+
+                if member == step {
+                    break;
+                }
+            }
+
+            self.components += 1;
+        }
+    }
+}
+
+pub fn part1(deps: &HashMap<char, Vec<char>>) -> String {
+    let mut left = deps.keys().cloned().collect::<BTreeSet<_>>();
+    let mut satisfied = HashSet::new();
+    let mut out = Vec::new();
+
+    while !left.is_empty() {
+        for c in left.iter().cloned() {
+            let ok = match deps.get(&c) {
+                Some(deps) => deps.iter().all(|dep| satisfied.contains(dep)),
+                None => true,
+            };
+
+            if ok {
+                out.push(c);
+                satisfied.insert(c);
+                left.remove(&c);
+                break;
+            }
+        }
+    }
+
+    out.into_iter().collect::<String>()
+}
+
+/// Events emitted while the workers assemble the sleigh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A worker started on a step.
+    Assign {
+        worker: usize,
+        step: char,
+        work: u32,
+    },
+    /// A worker finished a step.
+    Finish { worker: usize, step: char },
+}
+
+impl events::Event for Event {
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Assign { .. } => "assign",
+            Event::Finish { .. } => "finish",
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, Value)> {
+        match *self {
+            Event::Assign { worker, step, work } => vec![
+                ("worker", worker.into()),
+                ("step", step.into()),
+                ("work", work.into()),
+            ],
+            Event::Finish { worker, step } => {
+                vec![("worker", worker.into()), ("step", step.into())]
+            }
+        }
+    }
+}
+
+/// Calculate how many seconds it takes for the given number of workers to complete all steps.
+///
+/// Events are emitted with the second they happened in.
+pub fn part2(
+    deps: &HashMap<char, Vec<char>>,
+    base: u32,
+    worker_count: usize,
+    events: &mut Events<Event>,
+) -> Result<u32, Error> {
+    let mut left = deps.keys().cloned().collect::<BTreeSet<_>>();
+    let mut satisfied = HashSet::new();
+    let mut out = Vec::new();
+
+    let mut workers = std::iter::repeat(())
+        .map(|_| Worker {
+            work: 0u32,
+            current: None,
+        })
+        .take(worker_count)
+        .collect::<Vec<_>>();
+
+    let mut tick = 0;
+
+    loop {
+        tick += 1;
+
+        let mut idle = Vec::new();
+
+        for (n, worker) in workers.iter_mut().enumerate() {
+            worker.tick();
+
+            if worker.work == 0 {
+                if let Some(c) = worker.current.take() {
+                    out.push(c);
+                    satisfied.insert(c);
+                    events.emit(u64::from(tick - 1), Event::Finish { worker: n, step: c })?;
+                }
+
+                idle.push((n, worker));
+            }
+        }
+
+        if left.is_empty() && idle.len() == worker_count {
+            break;
+        }
+
+        // test if all dependencies are satisfied for the given node.
+        let test = |c: &char| match deps.get(c) {
+            Some(deps) => deps.iter().all(|dep| satisfied.contains(dep)),
+            None => true,
+        };
+
+        for ((n, w), c) in idle
+            .into_iter()
+            .zip(left.iter().cloned().filter(test))
+            .collect::<Vec<_>>()
+        {
+            w.work = base + (c as u32) - ('A' as u32) + 1;
+            w.current = Some(c);
+            left.remove(&c);
+
+            let event = Event::Assign {
+                worker: n,
+                step: c,
+                work: w.work,
+            };
+
+            events.emit(u64::from(tick - 1), event)?;
+        }
+    }
+
+    return Ok(tick - 1);
+
+    #[derive(Debug)]
+    struct Worker {
+        /// The amount of work left to do.
+        work: u32,
+        /// The current work.
+        current: Option<char>,
+    }
+
+    impl Worker {
+        fn tick(&mut self) {
+            self.work = self.work.saturating_sub(1);
+        }
+    }
+}
+
+/// Generate dependencies between `size` steps, up to the 26 letters of the alphabet.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut steps = ('A'..='Z').collect::<Vec<_>>();
+    rng.shuffle(&mut steps);
+    steps.truncate(size.clamp(2, 26));
+
+    let mut out = String::new();
+
+    // NB: steps only depend on earlier steps, which makes the graph acyclic. Every step after the
+    // first depends on at least one other step so that it is part of the input.
+    for (i, after) in steps.iter().enumerate().skip(1) {
+        let mut before = vec![steps[rng.below(i)]];
+
+        for other in &steps[..i] {
+            if !before.contains(other) && rng.chance(0.2) {
+                before.push(*other);
+            }
+        }
+
+        for before in before {
+            out.push_str(&format!(
+                "Step {} must be finished before step {} can begin.\n",
+                before, after
+            ));
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Log;
+
+    const EXAMPLE: &str = "Step C must be finished before step A can begin.
+Step C must be finished before step F can begin.
+Step A must be finished before step B can begin.
+Step A must be finished before step D can begin.
+Step B must be finished before step E can begin.
+Step D must be finished before step E can begin.
+Step F must be finished before step E can begin.
+";
+
+    #[test]
+    fn test_example() -> Result<(), Error> {
+        let deps = Day7::parse(EXAMPLE)?;
+        assert_eq!(part1(&deps), "CABDFE");
+
+        let log = Log::default();
+        assert_eq!(part2(&deps, 0, 2, &mut Events::log(&log))?, 15);
+
+        let assigned = log
+            .events()
+            .into_iter()
+            .filter_map(|(tick, e)| match e {
+                Event::Assign { worker, step, .. } => Some((tick, worker, step)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            (0, 0, 'C'),
+            (3, 0, 'A'),
+            (3, 1, 'F'),
+            (4, 0, 'B'),
+            (6, 0, 'D'),
+            (10, 0, 'E'),
+        ];
+
+        assert_eq!(assigned, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = "\
+Step A must be finished before step B can begin.
+Step B must be finished before step C can begin.
+Step C must be finished before step A can begin.
+Step C must be finished before step D can begin.
+Step x must be finished before step D can begin.
+Step E must be done before step D can begin.
+";
+
+        let errors = Day7::validate(input)
+            .into_iter()
+            .map(|e| (e.line(), e.columns(), e.message().to_string()))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            (5, 6..7, String::from("step `x` is not an uppercase letter")),
+            (
+                6,
+                6..6,
+                String::from("expected ` must be finished before step `"),
+            ),
+            (
+                1,
+                1..49,
+                String::from("steps A and B are part of a dependency cycle"),
+            ),
+            (
+                2,
+                1..49,
+                String::from("steps B and C are part of a dependency cycle"),
+            ),
+            (
+                3,
+                1..49,
+                String::from("steps C and A are part of a dependency cycle"),
+            ),
+        ];
+
+        assert_eq!(errors, expected);
+    }
+
+    #[test]
+    fn test_validate_cycles() {
+        // NB: C only sits between two cycles, and isn't part of either.
+        let input = "\
+Step A must be finished before step B can begin.
+Step B must be finished before step A can begin.
+Step B must be finished before step C can begin.
+Step C must be finished before step D can begin.
+Step D must be finished before step E can begin.
+Step E must be finished before step D can begin.
+Step F must be finished before step F can begin.
+";
+
+        let errors = Day7::validate(input)
+            .into_iter()
+            .map(|e| (e.line(), e.message().to_string()))
+            .collect::<Vec<_>>();
+
+        let expected = vec![
+            (
+                1,
+                String::from("steps A and B are part of a dependency cycle"),
+            ),
+            (
+                2,
+                String::from("steps B and A are part of a dependency cycle"),
+            ),
+            (
+                5,
+                String::from("steps D and E are part of a dependency cycle"),
+            ),
+            (
+                6,
+                String::from("steps E and D are part of a dependency cycle"),
+            ),
+            (7, String::from("step F depends on itself")),
+        ];
+
+        assert_eq!(errors, expected);
+    }
+}
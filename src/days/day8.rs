@@ -0,0 +1,206 @@
+use crate::gen::Rng;
+use crate::*;
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Node;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let values = columns!(input.as_bytes(), char::is_whitespace, u32);
+        let mut it = values.iter().cloned();
+        Node::decode(&mut it)
+    }
+
+    fn validate(input: &str) -> Vec<ParseError> {
+        let mut errors = Vec::new();
+        let mut values = Vec::new();
+
+        for (span, value) in split_columns(input) {
+            match str::parse::<u32>(value) {
+                Ok(value) => values.push((span, value)),
+                Err(e) => {
+                    let message = format!("bad `u32`: bad value `{}`: {}", value, e);
+                    errors.push(ParseError::locate(input, span, message));
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return errors;
+        }
+
+        let mut it = values.iter().map(|(_, value)| *value);
+
+        if let Err(e) = Node::decode(&mut it) {
+            let end = input.trim_end().len();
+            errors.push(ParseError::locate(input, end..end, e));
+        }
+
+        if let Some((span, _)) = values.get(values.len() - it.len()) {
+            let message = "unexpected value after the root node";
+            errors.push(ParseError::locate(input, span.clone(), message));
+        }
+
+        errors
+    }
+
+    fn part1(node: &Self::Input) -> Result<u32, Error> {
+        Ok(node.part1sum())
+    }
+
+    fn part2(node: &Self::Input) -> Result<u32, Error> {
+        Ok(node.part2sum())
+    }
+
+    fn generate(rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate(rng, size))
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct Node {
+    metadata: Vec<u32>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn part1sum(&self) -> u32 {
+        self.metadata.iter().cloned().sum::<u32>()
+            + self.children.iter().map(|c| c.part1sum()).sum::<u32>()
+    }
+
+    fn part2sum(&self) -> u32 {
+        if self.children.is_empty() {
+            self.metadata.iter().cloned().sum::<u32>()
+        } else {
+            let mut r = 0;
+
+            for m in self.metadata.iter().cloned() {
+                r += (m as usize)
+                    .checked_sub(1)
+                    .and_then(|m| self.children.get(m))
+                    .map(|c| c.part2sum())
+                    .unwrap_or_default();
+            }
+
+            r
+        }
+    }
+
+    fn decode(it: &mut impl Iterator<Item = u32>) -> Result<Node, Error> {
+        let children = it
+            .next()
+            .ok_or_else(|| format_err!("expected the number of children of a node"))?;
+
+        let mut node = Node::default();
+        let metadata = it
+            .next()
+            .ok_or_else(|| format_err!("expected the number of metadata entries of a node"))?;
+
+        for _ in 0..children {
+            node.children.push(Self::decode(it)?);
+        }
+
+        for _ in 0..metadata {
+            let value = it
+                .next()
+                .ok_or_else(|| format_err!("expected {} metadata entries", metadata))?;
+            node.metadata.push(value);
+        }
+
+        Ok(node)
+    }
+}
+
+/// Generate a license tree with `size` nodes.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut values = Vec::new();
+    let mut budget = size.max(1) - 1;
+    node(rng, &mut budget, 0, &mut values);
+
+    let values = values
+        .into_iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>();
+    return values.join(" ") + "\n";
+
+    fn node(rng: &mut Rng, budget: &mut usize, depth: usize, values: &mut Vec<u32>) {
+        let mut children = 0;
+
+        if depth < 8 {
+            children = rng.below(usize::min(*budget, 4) + 1);
+        }
+
+        *budget -= children;
+
+        values.push(children as u32);
+        let metadata = rng.range(1..5) as u32;
+        values.push(metadata);
+
+        for _ in 0..children {
+            node(rng, budget, depth + 1, values);
+        }
+
+        // NB: metadata can refer to missing children, but never to child zero.
+        for _ in 0..metadata {
+            values.push(rng.range(1..children as i64 + 3) as u32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let errors = |input: &str| {
+            Day8::validate(input)
+                .into_iter()
+                .map(|e| (e.line(), e.columns(), e.message().to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            Day8::validate("2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2"),
+            vec![]
+        );
+
+        assert_eq!(
+            errors("2 3 0 3 10 11 x 1 1 0 1 99 2 y 1 2"),
+            vec![
+                (
+                    1,
+                    15..16,
+                    String::from("bad `u32`: bad value `x`: invalid digit found in string")
+                ),
+                (
+                    1,
+                    30..31,
+                    String::from("bad `u32`: bad value `y`: invalid digit found in string")
+                ),
+            ]
+        );
+
+        assert_eq!(
+            errors("2 3 0 3 10 11 12\n"),
+            vec![(
+                1,
+                17..17,
+                String::from("expected the number of children of a node")
+            )]
+        );
+
+        assert_eq!(
+            errors("0 1 5 7"),
+            vec![(
+                1,
+                7..8,
+                String::from("unexpected value after the root node")
+            )]
+        );
+    }
+}
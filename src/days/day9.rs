@@ -0,0 +1,278 @@
+use crate::*;
+
+use std::fmt;
+use std::ptr;
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    /// The number of players, and the points of the last marble.
+    type Input = (u32, u32);
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, Error> {
+        let mut it = input.split(" ");
+        let players: u32 = str::parse(it.next().ok_or_else(|| format_err!("number of players"))?)?;
+        let highest: u32 = str::parse(it.nth(5).ok_or_else(|| format_err!("points"))?)?;
+        Ok((players, highest))
+    }
+
+    fn part1(&(players, highest): &Self::Input) -> Result<u32, Error> {
+        unsafe_game(players, highest).ok_or_else(|| format_err!("no players"))
+    }
+
+    fn part2(&(players, highest): &Self::Input) -> Result<u32, Error> {
+        unsafe_game(players, highest * 100).ok_or_else(|| format_err!("no players"))
+    }
+
+    fn variants() -> Vec<Variant<Self::Input>> {
+        // NB: `game` is too slow for part 2.
+        vec![Variant::new(
+            Part::One,
+            "game",
+            |&(players, highest): &(u32, u32)| {
+                game(players, highest).ok_or_else(|| format_err!("no players"))
+            },
+        )]
+    }
+}
+
+pub fn unsafe_game(players: u32, highest: u32) -> Option<u32> {
+    let mut cur = Node::new(0);
+    let mut scores = HashMap::<u32, u32>::new();
+
+    for (p, marble) in (0..players).cycle().zip(1..).take(highest as usize) {
+        if marble % 23 == 0 {
+            cur = cur.back(7);
+            let (next, last_marble) = cur.unlink();
+            *scores.entry(p).or_default() += marble + last_marble;
+            cur = next.expect("no more nodes");
+        } else {
+            cur = cur.forward(1).insert(marble);
+        }
+    }
+
+    scores.iter().max_by(|a, b| a.1.cmp(b.1)).map(|e| *e.1)
+}
+
+pub fn game(players: u32, highest: u32) -> Option<u32> {
+    let mut circle = VecDeque::new();
+    circle.push_back(0);
+
+    let mut cur = 0;
+    let mut scores = HashMap::<u32, u32>::new();
+
+    let it = (0..players).cycle().zip(1..).take(highest as usize);
+
+    for (p, marble) in it {
+        if marble % 23 == 0 {
+            let mut score = marble;
+
+            if cur < 7 {
+                cur = circle.len() - (7 - cur);
+            } else {
+                cur -= 7;
+            }
+
+            let last_marble = circle.remove(cur).unwrap_or_default();
+            score += last_marble;
+
+            let e = scores.entry(p).or_default();
+
+            *e += score;
+        } else {
+            cur += 2;
+
+            if cur > circle.len() {
+                cur -= circle.len();
+            }
+
+            circle.insert(cur, marble);
+        }
+    }
+
+    scores.iter().max_by(|a, b| a.1.cmp(b.1)).map(|e| *e.1)
+}
+
+// Note: following is a _very_ unsafe implementation of a linked list. But it was
+// the only way I could get this fast enough.
+struct Data {
+    prev: *mut Data,
+    next: *mut Data,
+    value: u32,
+}
+
+struct Node(*mut Data);
+
+impl Node {
+    fn new(value: u32) -> Node {
+        let n = Box::into_raw(Box::new(Data {
+            next: ptr::null_mut(),
+            prev: ptr::null_mut(),
+            value,
+        }));
+
+        unsafe {
+            (*n).next = n;
+            (*n).prev = n;
+        }
+
+        Node(n)
+    }
+
+    /// Rotate node back `c` steps.
+    fn back(mut self, c: usize) -> Node {
+        unsafe {
+            let mut data = self.0;
+
+            for _ in 0..c {
+                data = (*data).prev;
+            }
+
+            self.0 = data;
+        }
+
+        self
+    }
+
+    /// Rotate node forward `c` steps.
+    fn forward(mut self, c: usize) -> Node {
+        unsafe {
+            let mut data = self.0;
+
+            for _ in 0..c {
+                data = (*data).next;
+            }
+
+            self.0 = data;
+        }
+
+        self
+    }
+
+    /// Unlink the current node, returning the node immediately after this node, or `None`
+    /// if there is none.
+    fn unlink(mut self) -> (Option<Node>, u32) {
+        use std::mem;
+
+        let ptr = mem::replace(&mut self.0, ptr::null_mut());
+
+        unsafe {
+            // NB: only one node.
+            if (*ptr).next == ptr {
+                let c = Box::<Data>::from_raw(ptr);
+                return (None, c.value);
+            }
+
+            let c = Box::<Data>::from_raw(ptr);
+            (*c.prev).next = c.next;
+            (*c.next).prev = c.prev;
+            (Some(Node(c.next)), c.value)
+        }
+    }
+
+    /// Insert a node immediately after the current node, and return the inserted node.
+    fn insert(mut self, value: u32) -> Node {
+        unsafe {
+            let data = Box::into_raw(Box::new(Data {
+                next: (*self.0).next,
+                prev: self.0,
+                value,
+            }));
+
+            (*(*self.0).next).prev = data;
+            (*self.0).next = data;
+
+            self.0 = data;
+        }
+
+        self
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        // NB: Node that has been explicitly unlinked.
+        if self.0.is_null() {
+            return;
+        }
+
+        unsafe {
+            let s = self.0;
+            let mut c = self.0;
+
+            while (*c).next != s {
+                let d = c;
+                c = (*c).next;
+                drop(Box::from_raw(d));
+            }
+
+            drop(Box::from_raw(c));
+        }
+    }
+}
+
+// Note: only implemented to ease debugging.
+impl fmt::Debug for Node {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        unsafe {
+            let s = self.0;
+            let mut c = self.0;
+
+            while (*c).next != s {
+                write!(fmt, "{:?}, ", (*c).value)?;
+                c = (*c).next;
+            }
+
+            write!(fmt, "{:?}", (*c).value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLES: &[(u32, u32, u32)] = &[
+        (9, 25, 32),
+        (10, 1618, 8317),
+        (13, 7999, 146373),
+        (17, 1104, 2764),
+        (21, 6111, 54718),
+        (30, 5807, 37305),
+    ];
+
+    #[test]
+    fn test_game() {
+        for &(players, highest, expected) in EXAMPLES {
+            assert_eq!(game(players, highest), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_unsafe_game() {
+        for &(players, highest, expected) in EXAMPLES {
+            assert_eq!(unsafe_game(players, highest), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_game_and_unsafe_game_agree() {
+        gen::check(
+            300,
+            |rng| (rng.below(40) as u32, rng.below(3000) as u32),
+            |&(players, highest)| {
+                let (a, b) = (game(players, highest), unsafe_game(players, highest));
+
+                if a != b {
+                    bail!("game scored {:?}, but unsafe_game {:?}", a, b);
+                }
+
+                Ok(())
+            },
+        );
+    }
+}
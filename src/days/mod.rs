@@ -0,0 +1,61 @@
+//! Solutions for each day.
+
+use crate::Day;
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+macro_rules! days {
+    ($($day:expr => $ty:ty, $name:expr;)*) => {
+        vec![$(Day::new::<$ty>($day, $name, crate::input_str!($name)),)*]
+    };
+}
+
+/// Get all registered days, in order.
+pub fn all() -> Vec<Day> {
+    days! {
+        1 => day1::Day1, "day1.txt";
+        2 => day2::Day2, "day2.txt";
+        3 => day3::Day3, "day3.txt";
+        4 => day4::Day4, "day4.txt";
+        5 => day5::Day5, "day5.txt";
+        6 => day6::Day6, "day6.txt";
+        7 => day7::Day7, "day7.txt";
+        8 => day8::Day8, "day8.txt";
+        9 => day9::Day9, "day9.txt";
+        10 => day10::Day10, "day10.txt";
+        11 => day11::Day11, "day11.txt";
+        12 => day12::Day12, "day12.txt";
+        13 => day13::Day13, "day13.txt";
+        14 => day14::Day14, "day14.txt";
+        15 => day15::Day15, "day15.txt";
+        16 => day16::Day16, "day16.txt";
+        17 => day17::Day17, "day17.txt";
+        18 => day18::Day18, "day18.txt";
+        19 => day19::Day19, "day19.txt";
+        20 => day20::Day20, "day20.txt";
+    }
+}
+
+/// Get the given day, if it has been registered.
+pub fn get(day: u32) -> Option<Day> {
+    all().into_iter().find(|d| d.day == day)
+}
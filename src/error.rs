@@ -0,0 +1,239 @@
+use crate::Error;
+use std::error;
+use std::fmt;
+use std::ops::Range;
+
+/// An error raised while parsing input, pointing to where in the input it happened.
+///
+/// Renders with the offending line and a caret under the bad token.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// let e = ParseError::new(3, "#1 @ x,3: 4x4", 5..6, "bad `u32`: bad value `x`");
+/// let e = e.with_name("day3.txt");
+///
+/// assert_eq!(e.line(), 3);
+/// assert_eq!(e.columns(), 6..7);
+///
+/// let expected = "\
+/// day3.txt:3:6: bad `u32`: bad value `x`
+///   |
+/// 3 | #1 @ x,3: 4x4
+///   |      ^
+/// ";
+///
+/// assert_eq!(e.to_string(), expected);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    name: Option<String>,
+    line: usize,
+    columns: Range<usize>,
+    text: String,
+    message: String,
+}
+
+impl ParseError {
+    /// Construct an error for the given line.
+    ///
+    /// `line` is the 1-based line number, and `span` the byte range in `text` that is bad.
+    pub fn new(line: usize, text: &str, span: Range<usize>, message: impl fmt::Display) -> Self {
+        let text = text.trim_end_matches(['\n', '\r']);
+        let start = span.start.min(text.len());
+        let end = span.end.clamp(start, text.len());
+
+        let column = text[..start].chars().count() + 1;
+        let width = text[start..end].chars().count();
+
+        ParseError {
+            name: None,
+            line,
+            columns: column..column + width,
+            text: text.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Construct an error for a span in a larger input, which can span multiple lines.
+    ///
+    /// The error will point to the line that the span starts on.
+    pub fn locate(input: &str, span: Range<usize>, message: impl fmt::Display) -> Self {
+        let start = span.start.min(input.len());
+        let line_start = input[..start]
+            .rfind('\n')
+            .map(|n| n + 1)
+            .unwrap_or_default();
+        let line_end = input[start..]
+            .find('\n')
+            .map(|n| start + n)
+            .unwrap_or_else(|| input.len());
+
+        let line = input[..start].matches('\n').count() + 1;
+        let span = start - line_start..span.end.min(line_end).max(start) - line_start;
+        Self::new(line, &input[line_start..line_end], span, message)
+    }
+
+    /// Set the name of the input that the error occurred in.
+    pub fn with_name(mut self, name: impl AsRef<str>) -> Self {
+        self.name = Some(name.as_ref().to_string());
+        self
+    }
+
+    /// The name of the input, if known.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The 1-based line number of the error.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The 1-based range of characters on the line that are bad.
+    ///
+    /// This is empty if the error is about something missing at the given column.
+    pub fn columns(&self) -> Range<usize> {
+        self.columns.clone()
+    }
+
+    /// The text of the offending line.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The message describing the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Convert an error raised while parsing the given line into a parse error.
+    ///
+    /// Errors which already are parse errors keep their location, others point to the whole line.
+    pub fn from_error(line: usize, text: &str, error: Error) -> Self {
+        match error.downcast::<ParseError>() {
+            Ok(e) => e,
+            Err(e) => ParseError::new(line, text, 0..text.len(), e),
+        }
+    }
+
+    /// Set the input name of any parse error wrapped in the given error.
+    pub fn attach_name(mut error: Error, name: &str) -> Error {
+        if let Some(e) = error.downcast_mut::<ParseError>() {
+            e.name_if_missing(name);
+        }
+
+        if let Some(e) = error.downcast_mut::<ValidationError>() {
+            for e in &mut e.errors {
+                e.name_if_missing(name);
+            }
+        }
+
+        error
+    }
+
+    fn name_if_missing(&mut self, name: &str) {
+        if self.name.is_none() {
+            self.name = Some(name.to_string());
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = self.name.as_ref() {
+            write!(fmt, "{}:", name)?;
+        }
+
+        writeln!(
+            fmt,
+            "{}:{}: {}",
+            self.line, self.columns.start, self.message
+        )?;
+
+        let line = self.line.to_string();
+        let gutter = " ".repeat(line.len());
+        // NB: tabs are shown as spaces to keep the caret aligned.
+        let text = self.text.replace('\t', " ");
+        let indent = " ".repeat(self.columns.start - 1);
+        let carets = "^".repeat(self.columns.len().max(1));
+
+        writeln!(fmt, "{} |", gutter)?;
+        writeln!(fmt, "{} | {}", line, text)?;
+        writeln!(fmt, "{} | {}{}", gutter, indent, carets)?;
+        Ok(())
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Every problem found while validating an input, see [`Solution::validate`].
+///
+/// Renders each problem in the order they were found, followed by how many there were.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// let e = ValidationError::new(vec![
+///     ParseError::new(1, "1 x 3", 2..3, "bad value `x`"),
+///     ParseError::new(2, "4 5 y", 4..5, "bad value `y`"),
+/// ]);
+///
+/// assert_eq!(e.errors().len(), 2);
+///
+/// let expected = "\
+/// 1:3: bad value `x`
+///   |
+/// 1 | 1 x 3
+///   |   ^
+///
+/// 2:5: bad value `y`
+///   |
+/// 2 | 4 5 y
+///   |     ^
+///
+/// found 2 problems in input
+/// ";
+///
+/// assert_eq!(e.to_string(), expected);
+/// ```
+///
+/// [`Solution::validate`]: crate::Solution::validate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    errors: Vec<ParseError>,
+}
+
+impl ValidationError {
+    /// Construct from the problems found in an input.
+    pub fn new(errors: Vec<ParseError>) -> Self {
+        ValidationError { errors }
+    }
+
+    /// The problems found, in the order they were found.
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for e in &self.errors {
+            writeln!(fmt, "{}", e)?;
+        }
+
+        let plural = if self.errors.len() == 1 { "" } else { "s" };
+        writeln!(
+            fmt,
+            "found {} problem{} in input",
+            self.errors.len(),
+            plural
+        )
+    }
+}
+
+impl error::Error for ValidationError {}
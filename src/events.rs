@@ -0,0 +1,343 @@
+//! Typed events emitted by simulations.
+//!
+//! Simulations emit [`Event`]s through [`Events`], which hands them to a [`Sink`] together with the
+//! tick they happened in. Events can be written as JSON lines for external tooling, or be logged
+//! to make assertions on the intermediate steps of a simulation.
+//!
+//! [`EVENTS_ENV`] can be used to write the events of any simulation which supports it.
+
+use crate::{Error, Pos};
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+/// Environment variable consulted for a path to write events to, where `-` is stdout.
+pub const EVENTS_ENV: &str = "AOC_EVENTS";
+
+/// A value of a field in an event.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::events::Value;
+///
+/// assert_eq!(Value::from(42u64), Value::Int(42));
+/// assert_eq!(Value::from(u64::MAX), Value::UInt(u64::MAX));
+/// assert_eq!(Value::from(u64::MAX).to_string(), "18446744073709551615");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    /// An unsigned integer which is too large to be an [`Value::Int`].
+    UInt(u64),
+    Str(String),
+    /// A position, written as an `[x, y]` array.
+    Pos(i64, i64),
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Value::Int(value),
+            Err(_) => Value::UInt(value),
+        }
+    }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Self {
+        Value::Int(i64::from(value))
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Value::Int(value),
+            Err(_) => Value::UInt(value as u64),
+        }
+    }
+}
+
+impl From<char> for Value {
+    fn from(value: char) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_string())
+    }
+}
+
+impl From<(i64, i64)> for Value {
+    fn from((x, y): (i64, i64)) -> Self {
+        Value::Pos(x, y)
+    }
+}
+
+impl From<Pos> for Value {
+    fn from(pos: Pos) -> Self {
+        Value::Pos(pos.x, pos.y)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(value) => value.fmt(fmt),
+            Value::Int(value) => value.fmt(fmt),
+            Value::UInt(value) => value.fmt(fmt),
+            Value::Str(value) => JsonString(value).fmt(fmt),
+            Value::Pos(x, y) => write!(fmt, "[{}, {}]", x, y),
+        }
+    }
+}
+
+/// An event which can be written as JSON.
+pub trait Event {
+    /// The name of the event, which is written as its `event` field.
+    fn name(&self) -> &'static str;
+
+    /// The fields of the event, in the order they are written.
+    fn fields(&self) -> Vec<(&'static str, Value)>;
+}
+
+/// Format an event which happened in the given tick as a single line of JSON.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::events::{self, Event, Value};
+///
+/// struct Moved(&'static str, (i64, i64));
+///
+/// impl Event for Moved {
+///     fn name(&self) -> &'static str {
+///         "moved"
+///     }
+///
+///     fn fields(&self) -> Vec<(&'static str, Value)> {
+///         vec![("who", self.0.into()), ("to", self.1.into())]
+///     }
+/// }
+///
+/// assert_eq!(
+///     events::to_json(3, &Moved("\"cart\"", (1, -2))),
+///     r#"{"tick": 3, "event": "moved", "who": "\"cart\"", "to": [1, -2]}"#
+/// );
+/// ```
+pub fn to_json(tick: u64, event: &impl Event) -> String {
+    let mut out = format!(
+        "{{\"tick\": {}, \"event\": {}",
+        tick,
+        JsonString(event.name())
+    );
+
+    for (key, value) in event.fields() {
+        out.push_str(&format!(", {}: {}", JsonString(key), value));
+    }
+
+    out.push('}');
+    out
+}
+
+/// Something events can be emitted to.
+pub trait Sink<E> {
+    /// Emit an event which happened in the given tick.
+    fn emit(&mut self, tick: u64, event: &E) -> Result<(), Error>;
+}
+
+/// Emits events from a simulation to a sink, if any.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+/// use aoc2018::events::{Events, Log};
+///
+/// fn main() -> Result<(), Error> {
+///     let log = Log::default();
+///     let mut events = Events::log(&log);
+///
+///     for tick in 0..3 {
+///         events.emit(tick, tick * 2)?;
+///     }
+///
+///     assert_eq!(log.events(), vec![(0, 0), (1, 2), (2, 4)]);
+///
+///     // Nothing is emitted by default.
+///     let mut events = Events::noop();
+///     assert!(!events.is_enabled());
+///     events.emit(0, "ignored")?;
+///     Ok(())
+/// }
+/// ```
+pub struct Events<E> {
+    sink: Option<Box<dyn Sink<E>>>,
+}
+
+impl<E> Events<E> {
+    /// Events which aren't emitted anywhere.
+    pub fn noop() -> Events<E> {
+        Events { sink: None }
+    }
+
+    /// Events which are emitted to the given sink.
+    pub fn new(sink: impl Sink<E> + 'static) -> Events<E> {
+        Events {
+            sink: Some(Box::new(sink)),
+        }
+    }
+
+    /// Events which are written as JSON lines to the given output.
+    pub fn jsonl(out: impl Write + 'static) -> Events<E>
+    where
+        E: Event,
+    {
+        Events::new(Jsonl(out))
+    }
+
+    /// Events which are logged to the given log.
+    pub fn log(log: &Log<E>) -> Events<E>
+    where
+        E: 'static + Clone,
+    {
+        Events::new(log.clone())
+    }
+
+    /// Test if events are emitted anywhere.
+    pub fn is_enabled(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    /// Emit an event which happened in the given tick.
+    pub fn emit(&mut self, tick: u64, event: E) -> Result<(), Error> {
+        match self.sink.as_mut() {
+            Some(sink) => sink.emit(tick, &event),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<E> Default for Events<E> {
+    fn default() -> Self {
+        Events::noop()
+    }
+}
+
+/// Events written as JSON lines to the path in [`EVENTS_ENV`], or no events if it isn't set.
+pub fn from_env<E>() -> Result<Events<E>, Error>
+where
+    E: Event,
+{
+    let path = match env::var_os(EVENTS_ENV) {
+        Some(path) => path,
+        None => return Ok(Events::noop()),
+    };
+
+    if path == "-" {
+        return Ok(Events::jsonl(io::stdout()));
+    }
+
+    Ok(Events::jsonl(io::BufWriter::new(fs::File::create(path)?)))
+}
+
+/// Writes events as JSON lines.
+struct Jsonl<W>(W);
+
+impl<W, E> Sink<E> for Jsonl<W>
+where
+    W: Write,
+    E: Event,
+{
+    fn emit(&mut self, tick: u64, event: &E) -> Result<(), Error> {
+        writeln!(self.0, "{}", to_json(tick, event))?;
+        Ok(())
+    }
+}
+
+/// Events logged by a logger, together with the tick they happened in.
+///
+/// This is a handle which can be cloned, where all clones refer to the same log.
+#[derive(Debug)]
+pub struct Log<E> {
+    inner: Rc<RefCell<Vec<(u64, E)>>>,
+}
+
+impl<E> Log<E> {
+    /// Get a copy of all logged events.
+    pub fn events(&self) -> Vec<(u64, E)>
+    where
+        E: Clone,
+    {
+        self.inner.borrow().clone()
+    }
+}
+
+impl<E> Clone for Log<E> {
+    fn clone(&self) -> Self {
+        Log {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<E> Default for Log<E> {
+    fn default() -> Self {
+        Log {
+            inner: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+}
+
+impl<E> Sink<E> for Log<E>
+where
+    E: Clone,
+{
+    fn emit(&mut self, tick: u64, event: &E) -> Result<(), Error> {
+        self.inner.borrow_mut().push((tick, event.clone()));
+        Ok(())
+    }
+}
+
+/// Formats a string as a quoted JSON string.
+pub(crate) struct JsonString<'a>(pub(crate) &'a str);
+
+impl fmt::Display for JsonString<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "\"".fmt(fmt)?;
+
+        for c in self.0.chars() {
+            match c {
+                '"' => "\\\"".fmt(fmt)?,
+                '\\' => "\\\\".fmt(fmt)?,
+                '\n' => "\\n".fmt(fmt)?,
+                '\r' => "\\r".fmt(fmt)?,
+                '\t' => "\\t".fmt(fmt)?,
+                c if (c as u32) < 0x20 => write!(fmt, "\\u{:04x}", c as u32)?,
+                c => c.fmt(fmt)?,
+            }
+        }
+
+        "\"".fmt(fmt)
+    }
+}
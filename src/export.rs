@@ -0,0 +1,941 @@
+//! Headless export of frames to images.
+//!
+//! Frames are rendered to [`Image`]s by looking up every cell in a [`Palette`], which maps the
+//! characters that a simulation uses for its tiles to colors. Images can be written as PPM or PNG,
+//! and a sequence of them as an animated GIF.
+//!
+//! The [`Exporter`] backend plugs this into [`Visuals`], and [`EXPORT_ENV`] can be used to export
+//! from any simulation which supports it. Paths ending in `.cast` are recorded as text with the
+//! [`Asciicast`](crate::visuals::Asciicast) backend instead, which doesn't need a palette.
+
+use crate::visuals::{Backend, Frame, Visuals};
+use crate::{bail, format_err, Context, Error};
+use hashbrown::HashMap;
+use std::convert::TryFrom;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Environment variable consulted for a path to export visualizations to.
+pub const EXPORT_ENV: &str = "AOC_EXPORT";
+
+/// A color, as red, green and blue.
+pub type Rgb = [u8; 3];
+
+/// Colors to use for the characters of a frame.
+///
+/// The first color is the background, which is used for any characters without a color.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    colors: Vec<Rgb>,
+    indexes: HashMap<char, u8>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::new([0, 0, 0])
+    }
+}
+
+impl Palette {
+    /// Construct a palette with the given background color.
+    pub fn new(background: Rgb) -> Palette {
+        Palette {
+            colors: vec![background],
+            indexes: HashMap::new(),
+        }
+    }
+
+    /// Use the given color for the given character.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the palette has more than 256 colors.
+    pub fn color(mut self, c: char, rgb: Rgb) -> Self {
+        let index = match self.colors.iter().position(|o| *o == rgb) {
+            Some(index) => index,
+            None => {
+                self.colors.push(rgb);
+                self.colors.len() - 1
+            }
+        };
+
+        assert!(index < 256, "palettes are limited to 256 colors");
+        self.indexes.insert(c, index as u8);
+        self
+    }
+
+    /// The colors of the palette.
+    pub fn colors(&self) -> &[Rgb] {
+        &self.colors
+    }
+
+    /// Get the index of the color for the given character.
+    pub fn index(&self, c: char) -> u8 {
+        self.indexes.get(&c).cloned().unwrap_or_default()
+    }
+}
+
+/// An image, where every pixel is an index into a palette.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+/// use aoc2018::export::{Image, Palette};
+/// use aoc2018::visuals::Frame;
+///
+/// fn main() -> Result<(), Error> {
+///     let palette = Palette::new([0, 0, 0]).color('#', [255, 255, 255]);
+///
+///     let mut frame = Frame::new();
+///     frame.print(0, 0, "#.\n.#");
+///
+///     let image = Image::render(&frame, &palette, 1);
+///     assert_eq!((image.width(), image.height()), (2, 2));
+///
+///     let mut ppm = Vec::new();
+///     image.write_ppm(&mut ppm)?;
+///     assert_eq!(&ppm[..11], b"P6\n2 2\n255\n");
+///     assert_eq!(&ppm[11..17], &[255, 255, 255, 0, 0, 0]);
+///
+///     let mut png = Vec::new();
+///     image.write_png(&mut png)?;
+///     assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+    colors: Vec<Rgb>,
+}
+
+impl Image {
+    /// Render a frame, where every cell is a square of `scale` pixels.
+    pub fn render(frame: &Frame, palette: &Palette, scale: usize) -> Image {
+        let scale = scale.max(1);
+        let columns = frame
+            .rows()
+            .iter()
+            .map(|r| r.len())
+            .max()
+            .unwrap_or_default();
+
+        let width = columns * scale;
+        let height = frame.height() * scale;
+        let mut pixels = vec![0; width * height];
+
+        for (y, row) in frame.rows().iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let index = palette.index(cell.c);
+
+                for py in y * scale..(y + 1) * scale {
+                    let start = py * width + x * scale;
+                    pixels[start..start + scale]
+                        .iter_mut()
+                        .for_each(|p| *p = index);
+                }
+            }
+        }
+
+        Image {
+            width,
+            height,
+            pixels,
+            colors: palette.colors().to_vec(),
+        }
+    }
+
+    /// The width of the image in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the image in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Get the color of the given pixel.
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x >= self.width {
+            return None;
+        }
+
+        let index = *self.pixels.get(y * self.width + x)?;
+        self.colors.get(index as usize).cloned()
+    }
+
+    /// Resize the image without scaling, filling any new pixels with the background.
+    fn resize(&self, width: usize, height: usize) -> Image {
+        let mut pixels = vec![0; width * height];
+
+        for y in 0..usize::min(height, self.height) {
+            let n = usize::min(width, self.width);
+            let from = y * self.width;
+            pixels[y * width..y * width + n].copy_from_slice(&self.pixels[from..from + n]);
+        }
+
+        Image {
+            width,
+            height,
+            pixels,
+            colors: self.colors.clone(),
+        }
+    }
+
+    /// Write the image as a binary PPM.
+    pub fn write_ppm(&self, mut out: impl Write) -> Result<(), Error> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+
+        let mut data = Vec::with_capacity(self.pixels.len() * 3);
+
+        for index in self.pixels.iter().cloned() {
+            data.extend_from_slice(&self.colors[index as usize]);
+        }
+
+        out.write_all(&data)?;
+        Ok(())
+    }
+
+    /// Write the image as an indexed PNG.
+    pub fn write_png(&self, mut out: impl Write) -> Result<(), Error> {
+        let width = u32::try_from(self.width).context("image too wide")?;
+        let height = u32::try_from(self.height).context("image too tall")?;
+
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        // bit depth 8, indexed color, default compression, filtering and no interlacing.
+        header.extend_from_slice(&[8, 3, 0, 0, 0]);
+        png_chunk(&mut out, b"IHDR", &header)?;
+
+        let palette = self.colors.iter().flatten().cloned().collect::<Vec<_>>();
+        png_chunk(&mut out, b"PLTE", &palette)?;
+
+        let mut raw = Vec::with_capacity((self.width + 1) * self.height);
+
+        for row in self.pixels.chunks(self.width.max(1)) {
+            // NB: no filtering.
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+
+        png_chunk(&mut out, b"IDAT", &zlib(&raw))?;
+        png_chunk(&mut out, b"IEND", &[])?;
+        Ok(())
+    }
+}
+
+/// Write an animated GIF which loops forever.
+///
+/// Each image is shown for its given duration, rounded to hundredths of a second. Images of
+/// different sizes are padded with the background to the size of the largest one.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+/// use aoc2018::export::{self, Image, Palette};
+/// use aoc2018::visuals::Frame;
+/// use std::time::Duration;
+///
+/// fn main() -> Result<(), Error> {
+///     let palette = Palette::new([0, 0, 0]).color('#', [255, 255, 255]);
+///     let mut images = Vec::new();
+///
+///     for n in 0..3 {
+///         let mut frame = Frame::new();
+///         frame.print(n, n, "#");
+///         images.push((Duration::from_millis(100), Image::render(&frame, &palette, 2)));
+///     }
+///
+///     let mut gif = Vec::new();
+///     export::write_gif(&mut gif, &images)?;
+///
+///     assert_eq!(&gif[..6], b"GIF89a");
+///     // padded to the largest frame.
+///     assert_eq!(&gif[6..10], &[6, 0, 6, 0]);
+///     assert_eq!(gif.last(), Some(&0x3b));
+///     Ok(())
+/// }
+/// ```
+pub fn write_gif(mut out: impl Write, images: &[(Duration, Image)]) -> Result<(), Error> {
+    let width = images
+        .iter()
+        .map(|(_, i)| i.width)
+        .max()
+        .unwrap_or_default();
+    let height = images
+        .iter()
+        .map(|(_, i)| i.height)
+        .max()
+        .unwrap_or_default();
+
+    if width > usize::from(u16::MAX) || height > usize::from(u16::MAX) {
+        bail!("image too large for gif: {}x{}", width, height);
+    }
+
+    let colors = images
+        .first()
+        .map(|(_, i)| i.colors.clone())
+        .unwrap_or_else(|| vec![[0, 0, 0]]);
+
+    // NB: the color table has 2^(n + 1) entries, and codes need at least 2 bits.
+    let bits = (1..=8).find(|b| colors.len() <= 1 << b).unwrap_or(8).max(2);
+
+    out.write_all(b"GIF89a")?;
+    out.write_all(&(width as u16).to_le_bytes())?;
+    out.write_all(&(height as u16).to_le_bytes())?;
+    out.write_all(&[0xf0 | (bits - 1), 0, 0])?;
+
+    for n in 0..1usize << bits {
+        out.write_all(&colors.get(n).cloned().unwrap_or_default())?;
+    }
+
+    // loop forever.
+    out.write_all(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00")?;
+
+    for (duration, image) in images {
+        if image.colors != colors {
+            bail!("all images in a gif must use the same palette");
+        }
+
+        let image = image.resize(width, height);
+        let delay = u16::try_from((duration.as_millis() + 5) / 10).unwrap_or(u16::MAX);
+
+        out.write_all(&[0x21, 0xf9, 0x04, 0x00])?;
+        out.write_all(&delay.to_le_bytes())?;
+        out.write_all(&[0x00, 0x00])?;
+
+        out.write_all(&[0x2c, 0, 0, 0, 0])?;
+        out.write_all(&(width as u16).to_le_bytes())?;
+        out.write_all(&(height as u16).to_le_bytes())?;
+        out.write_all(&[0x00, bits])?;
+
+        for block in lzw(bits, &image.pixels).chunks(255) {
+            out.write_all(&[block.len() as u8])?;
+            out.write_all(block)?;
+        }
+
+        out.write_all(&[0x00])?;
+    }
+
+    out.write_all(&[0x3b])?;
+    Ok(())
+}
+
+/// Write a single PNG chunk.
+fn png_chunk(mut out: impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+
+    let crc = crc32(kind.iter().chain(data.iter()).cloned());
+    out.write_all(&crc.to_be_bytes())
+}
+
+/// CRC-32 as used by PNG.
+fn crc32(data: impl IntoIterator<Item = u8>) -> u32 {
+    let mut crc = !0u32;
+
+    for b in data {
+        crc ^= u32::from(b);
+
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+/// The smallest lengths which can be encoded by each deflate length code, and their extra bits.
+const LENGTHS: [(u16, u8); 29] = [
+    (3, 0),
+    (4, 0),
+    (5, 0),
+    (6, 0),
+    (7, 0),
+    (8, 0),
+    (9, 0),
+    (10, 0),
+    (11, 1),
+    (13, 1),
+    (15, 1),
+    (17, 1),
+    (19, 2),
+    (23, 2),
+    (27, 2),
+    (31, 2),
+    (35, 3),
+    (43, 3),
+    (51, 3),
+    (59, 3),
+    (67, 4),
+    (83, 4),
+    (99, 4),
+    (115, 4),
+    (131, 5),
+    (163, 5),
+    (195, 5),
+    (227, 5),
+    (258, 0),
+];
+
+/// The smallest distances which can be encoded by each deflate distance code, and their extra bits.
+const DISTANCES: [(u16, u8); 30] = [
+    (1, 0),
+    (2, 0),
+    (3, 0),
+    (4, 0),
+    (5, 1),
+    (7, 1),
+    (9, 2),
+    (13, 2),
+    (17, 3),
+    (25, 3),
+    (33, 4),
+    (49, 4),
+    (65, 5),
+    (97, 5),
+    (129, 6),
+    (193, 6),
+    (257, 7),
+    (385, 7),
+    (513, 8),
+    (769, 8),
+    (1025, 9),
+    (1537, 9),
+    (2049, 10),
+    (3073, 10),
+    (4097, 11),
+    (6145, 11),
+    (8193, 12),
+    (12289, 12),
+    (16385, 13),
+    (24577, 13),
+];
+
+/// How far back deflate can refer to earlier data.
+const WINDOW: usize = 32768;
+
+/// How many earlier positions to try before settling for the longest match found so far.
+const MAX_CHAIN: usize = 64;
+
+/// Compress data into a zlib stream, as a single deflate block with the fixed Huffman codes.
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut bits = Bits::default();
+
+    // final block, compressed with fixed codes.
+    bits.write(1, 1);
+    bits.write(1, 2);
+
+    let mut matches = Matches::new(data);
+    let mut i = 0;
+
+    while i < data.len() {
+        let (len, distance) = matches.longest(i);
+
+        if len < 3 {
+            write_symbol(&mut bits, u16::from(data[i]));
+            matches.insert(i);
+            i += 1;
+            continue;
+        }
+
+        let (n, extra, size) = code(&LENGTHS, len);
+        write_symbol(&mut bits, 257 + n);
+        bits.write(extra, size);
+
+        let (n, extra, size) = code(&DISTANCES, distance);
+        bits.write(reverse(n, 5), 5);
+        bits.write(extra, size);
+
+        for p in i..i + usize::from(len) {
+            matches.insert(p);
+        }
+
+        i += usize::from(len);
+    }
+
+    write_symbol(&mut bits, 256);
+
+    let mut out = vec![0x78, 0x01];
+    out.extend(bits.finish());
+
+    let (mut a, mut b) = (1u32, 0u32);
+
+    for byte in data {
+        a = (a + u32::from(*byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    out.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    out
+}
+
+/// Earlier positions in the data, by the three bytes starting at them.
+struct Matches<'a> {
+    data: &'a [u8],
+    /// The most recent position of every three bytes.
+    head: HashMap<[u8; 3], usize>,
+    /// The position before each position which starts with the same three bytes.
+    prev: Vec<Option<usize>>,
+}
+
+impl<'a> Matches<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Matches {
+            data,
+            head: HashMap::new(),
+            prev: vec![None; data.len()],
+        }
+    }
+
+    /// The three bytes starting at the given position, if there are that many left.
+    fn key(&self, p: usize) -> Option<[u8; 3]> {
+        let key = self.data.get(p..p + 3)?;
+        Some([key[0], key[1], key[2]])
+    }
+
+    fn insert(&mut self, p: usize) {
+        if let Some(key) = self.key(p) {
+            self.prev[p] = self.head.insert(key, p);
+        }
+    }
+
+    /// Find the longest earlier match for the data at the given position, as its length and
+    /// distance.
+    fn longest(&self, i: usize) -> (u16, u16) {
+        let mut candidate = match self.key(i) {
+            Some(key) => self.head.get(&key).cloned(),
+            None => return (0, 0),
+        };
+
+        let max = usize::min(258, self.data.len() - i);
+        let mut best = (0, 0);
+
+        for _ in 0..MAX_CHAIN {
+            let j = match candidate {
+                Some(j) if i - j <= WINDOW => j,
+                _ => break,
+            };
+
+            // NB: matches may overlap the data they produce, which deflate copies one byte at a
+            // time.
+            let len = (0..max)
+                .take_while(|&n| self.data[j + n] == self.data[i + n])
+                .count();
+
+            if len > best.0 {
+                best = (len, i - j);
+
+                if len == max {
+                    break;
+                }
+            }
+
+            candidate = self.prev[j];
+        }
+
+        (best.0 as u16, best.1 as u16)
+    }
+}
+
+/// Find the code in a table of lengths or distances that a value falls in, together with the
+/// value of its extra bits and how many there are.
+fn code(table: &[(u16, u8)], value: u16) -> (u16, u16, u8) {
+    let n = table
+        .iter()
+        .rposition(|&(base, _)| base <= value)
+        .expect("value in table");
+
+    let (base, size) = table[n];
+    (n as u16, value - base, size)
+}
+
+/// Write a literal or length symbol with the fixed Huffman codes.
+fn write_symbol(bits: &mut Bits, symbol: u16) {
+    let (code, size) = match symbol {
+        0..=143 => (0x30 + symbol, 8),
+        144..=255 => (0x190 + symbol - 144, 9),
+        256..=279 => (symbol - 256, 7),
+        _ => (0xc0 + symbol - 280, 8),
+    };
+
+    bits.write(reverse(code, size), size);
+}
+
+/// Reverse the lowest `size` bits of a Huffman code, since they're packed most significant bit
+/// first.
+fn reverse(code: u16, size: u8) -> u16 {
+    code.reverse_bits() >> (16 - size)
+}
+
+/// Compress indexes with the variable-width LZW used by GIF.
+fn lzw(min_size: u8, data: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << min_size;
+    let end = clear + 1;
+
+    let mut out = Bits::default();
+    let mut size = min_size + 1;
+    let mut codes = HashMap::<(u16, u8), u16>::new();
+    let mut next = end + 1;
+
+    out.write(clear, size);
+
+    let mut it = data.iter().cloned();
+
+    let mut prefix = match it.next() {
+        Some(b) => u16::from(b),
+        None => {
+            out.write(end, size);
+            return out.finish();
+        }
+    };
+
+    for b in it {
+        if let Some(code) = codes.get(&(prefix, b)).cloned() {
+            prefix = code;
+            continue;
+        }
+
+        out.write(prefix, size);
+
+        if next == 4096 {
+            out.write(clear, size);
+            codes.clear();
+            next = end + 1;
+            size = min_size + 1;
+        } else {
+            codes.insert((prefix, b), next);
+            next += 1;
+
+            // NB: the decoder lags one code behind, so grow once it has filled the current size.
+            if next > 1 << size && size < 12 {
+                size += 1;
+            }
+        }
+
+        prefix = u16::from(b);
+    }
+
+    out.write(prefix, size);
+    out.write(end, size);
+    out.finish()
+}
+
+/// Packs codes least significant bit first.
+#[derive(Default)]
+struct Bits {
+    out: Vec<u8>,
+    buffer: u32,
+    len: u8,
+}
+
+impl Bits {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= u32::from(code) << self.len;
+        self.len += size;
+
+        while self.len >= 8 {
+            self.out.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.out.push(self.buffer as u8);
+        }
+
+        self.out
+    }
+}
+
+/// Image formats that can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A binary PPM per frame.
+    Ppm,
+    /// A PNG per frame.
+    Png,
+    /// A single animated GIF.
+    Gif,
+}
+
+impl Format {
+    /// Determine the format from the extension of a path.
+    pub fn from_path(path: &Path) -> Result<Format, Error> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .ok_or_else(|| format_err!("no extension on export path: {}", path.display()))?;
+
+        match ext {
+            "ppm" => Ok(Format::Ppm),
+            "png" => Ok(Format::Png),
+            "gif" => Ok(Format::Gif),
+            other => bail!("unsupported export format: {}", other),
+        }
+    }
+}
+
+/// A backend which exports frames as images.
+///
+/// Image sequences are written as numbered files next to the given path, so `out/frame.png`
+/// is exported to `out/frame-00000.png`, `out/frame-00001.png` and so on. A GIF is written to the
+/// given path once the visuals are done.
+pub struct Exporter {
+    path: PathBuf,
+    format: Format,
+    palette: Palette,
+    scale: usize,
+    clock: Duration,
+    frames: Vec<(Duration, Image)>,
+    count: usize,
+}
+
+impl Exporter {
+    /// Construct an exporter to the given path, with a format based on its extension.
+    pub fn new(path: impl AsRef<Path>, palette: Palette) -> Result<Exporter, Error> {
+        let path = path.as_ref().to_owned();
+        let format = Format::from_path(&path)?;
+
+        Ok(Exporter {
+            path,
+            format,
+            palette,
+            scale: 4,
+            clock: Duration::default(),
+            frames: Vec::new(),
+            count: 0,
+        })
+    }
+
+    /// The number of pixels to use for every cell, defaults to 4.
+    pub fn scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// The path that the given frame is written to, for image sequences.
+    fn frame_path(&self, n: usize) -> PathBuf {
+        let stem = self
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let ext = self
+            .path
+            .extension()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        self.path
+            .with_file_name(format!("{}-{:05}.{}", stem, n, ext))
+    }
+
+    /// Finish the frame that is being shown, so that its duration is known.
+    fn finish_frame(&mut self) {
+        if let Some((start, _)) = self.frames.last_mut() {
+            *start = self.clock - *start;
+        }
+    }
+}
+
+impl Backend for Exporter {
+    fn setup(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &Frame) -> Result<(), Error> {
+        let image = Image::render(frame, &self.palette, self.scale);
+        let n = self.count;
+        self.count += 1;
+
+        match self.format {
+            Format::Gif => {
+                self.finish_frame();
+                // NB: the start time is replaced with the duration once the next frame is drawn.
+                self.frames.push((self.clock, image));
+                return Ok(());
+            }
+            Format::Ppm | Format::Png => {}
+        }
+
+        let path = self.frame_path(n);
+        let mut out = io::BufWriter::new(
+            fs::File::create(&path)
+                .with_context(|| format!("failed to create: {}", path.display()))?,
+        );
+
+        match self.format {
+            Format::Ppm => image.write_ppm(&mut out)?,
+            _ => image.write_png(&mut out)?,
+        }
+
+        out.flush()?;
+        Ok(())
+    }
+
+    fn pause(&mut self, duration: Duration) -> Result<(), Error> {
+        self.clock += duration;
+        Ok(())
+    }
+
+    fn key(&mut self) -> Result<Option<char>, Error> {
+        Ok(None)
+    }
+
+    fn done(&mut self, _: &str, _: bool) -> Result<(), Error> {
+        if self.format != Format::Gif {
+            return Ok(());
+        }
+
+        // NB: show the last frame for a while.
+        self.clock += Duration::from_secs(2);
+        self.finish_frame();
+
+        let path = &self.path;
+        let out = fs::File::create(path)
+            .with_context(|| format!("failed to create: {}", path.display()))?;
+
+        let mut out = io::BufWriter::new(out);
+        write_gif(&mut out, &self.frames)?;
+        out.flush()?;
+        self.frames.clear();
+        Ok(())
+    }
+}
+
+/// Visuals which export to the path in [`EXPORT_ENV`], or `None` if it isn't set.
+pub fn from_env(palette: Palette) -> Result<Option<Visuals>, Error> {
+    let path = match env::var_os(EXPORT_ENV) {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+
+    let visuals = if Path::new(&path).extension() == Some("cast".as_ref()) {
+        Visuals::asciicast(path)
+    } else {
+        Visuals::new(Exporter::new(path, palette)?)
+    };
+
+    Ok(Some(visuals.sleep(Duration::from_millis(100))))
+}
+
+/// Export visualizations to the path in [`EXPORT_ENV`] if it is set, or use the given visuals.
+pub fn from_env_or(palette: Palette, visuals: Visuals) -> Result<Visuals, Error> {
+    Ok(from_env(palette)?.unwrap_or(visuals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decompress a zlib stream of deflate blocks with fixed codes, which is all `zlib` writes.
+    fn inflate(data: &[u8]) -> Vec<u8> {
+        let mut pos = 16;
+
+        let mut read = |size: usize| {
+            let mut value = 0;
+
+            for n in 0..size {
+                let bit = (data[pos / 8] >> (pos % 8)) & 1;
+                value |= usize::from(bit) << n;
+                pos += 1;
+            }
+
+            value
+        };
+
+        // NB: Huffman codes are read one bit at a time, most significant bit first.
+        let huffman = |read: &mut dyn FnMut(usize) -> usize, size: usize| {
+            (0..size).fold(0, |code, _| code << 1 | read(1))
+        };
+
+        let mut out = Vec::new();
+
+        loop {
+            let last = read(1) == 1;
+            assert_eq!(read(2), 1, "expected fixed codes");
+
+            loop {
+                let mut symbol = huffman(&mut read, 7);
+
+                symbol = match symbol {
+                    0..=0x17 => symbol + 256,
+                    _ => match symbol << 1 | read(1) {
+                        code @ 0x30..=0xbf => code - 0x30,
+                        code @ 0xc0..=0xc7 => code - 0xc0 + 280,
+                        code => (code << 1 | read(1)) - 0x190 + 144,
+                    },
+                };
+
+                if symbol < 256 {
+                    out.push(symbol as u8);
+                    continue;
+                }
+
+                if symbol == 256 {
+                    break;
+                }
+
+                let (base, size) = LENGTHS[symbol - 257];
+                let len = usize::from(base) + read(usize::from(size));
+                let (base, size) = DISTANCES[huffman(&mut read, 5)];
+                let distance = usize::from(base) + read(usize::from(size));
+
+                for _ in 0..len {
+                    out.push(out[out.len() - distance]);
+                }
+            }
+
+            if last {
+                break;
+            }
+        }
+
+        out
+    }
+
+    #[test]
+    fn test_zlib() {
+        let rows = (0..200u32)
+            .flat_map(|y| (0..300u32).map(move |x| ((x / 7 + y / 5) % 4) as u8))
+            .collect::<Vec<_>>();
+
+        let inputs = vec![
+            Vec::new(),
+            vec![42],
+            b"abcabcabcabcabc".to_vec(),
+            vec![0; 100_000],
+            (0..=255).cycle().take(70_000).collect(),
+            rows,
+        ];
+
+        for input in inputs {
+            let compressed = zlib(&input);
+            assert_eq!(&compressed[..2], &[0x78, 0x01]);
+            assert_eq!(inflate(&compressed), input);
+        }
+
+        // frames are mostly runs of the same few colors, which compress well.
+        assert!(zlib(&vec![1; 100_000]).len() < 1_000);
+    }
+}
@@ -0,0 +1,377 @@
+//! Seeded generators of random puzzle inputs.
+//!
+//! Days which support it implement [`Solution::generate`], which builds a random input in the same
+//! format as the puzzle input from an [`Rng`] and a size. The meaning of the size depends on the
+//! day, like the number of claims or the width of a map, but larger sizes always give larger
+//! inputs. The same seed and size always give the same input.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use aoc2018::*;
+//!
+//! fn main() -> Result<(), Error> {
+//!     for day in days::all() {
+//!         for seed in 0..3 {
+//!             let input = match day.generate(seed, 8) {
+//!                 Some(input) => input,
+//!                 None => continue,
+//!             };
+//!
+//!             let again = day.generate(seed, 8).expect("generated input");
+//!             assert_eq!(input.as_str(), again.as_str());
+//!
+//!             for answer in day.run(&input, &Part::ALL)? {
+//!                 if let Err(e) = answer.value {
+//!                     panic!("day {} failed on seed {}: {}", day.day, seed, e);
+//!                 }
+//!             }
+//!         }
+//!     }
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! Generated values can also be used to [`check`] properties, like that two implementations of a
+//! solution agree, where any failure is shrunk into a minimal counter-example through [`Shrink`].
+//!
+//! [`Solution::generate`]: crate::Solution::generate
+
+use crate::{Error, HashMap};
+use std::any::Any;
+use std::fmt;
+use std::hash::Hash;
+use std::ops;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A small and fast pseudo-random number generator, based on SplitMix64.
+///
+/// This is not suitable for anything but generating test data.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::gen::Rng;
+///
+/// let mut a = Rng::new(42);
+/// let mut b = Rng::new(42);
+///
+/// let values = (0..10).map(|_| a.range(-5..5)).collect::<Vec<_>>();
+/// assert_eq!(values, (0..10).map(|_| b.range(-5..5)).collect::<Vec<_>>());
+/// assert!(values.iter().all(|v| (-5..5).contains(v)));
+///
+/// let mut items = vec![1, 2, 3, 4];
+/// a.shuffle(&mut items);
+/// items.sort();
+/// assert_eq!(items, vec![1, 2, 3, 4]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Construct a generator from the given seed.
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Generate the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Generate a number in `0..n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "cannot generate a number below zero");
+        // NB: the modulo bias is negligible for the small ranges used here.
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Generate a number in the given range.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is empty.
+    pub fn range(&mut self, range: ops::Range<i64>) -> i64 {
+        assert!(
+            range.start < range.end,
+            "cannot generate from an empty range"
+        );
+        let n = (range.end - range.start) as u64;
+        range.start + (self.next_u64() % n) as i64
+    }
+
+    /// Test a condition which is true with the given probability.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    /// Pick a random item, or `None` if there are none.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+
+        items.get(self.below(items.len()))
+    }
+
+    /// Shuffle the given items.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Check that a property holds for `cases` generated values.
+///
+/// Every case is generated from an [`Rng`] seeded with the number of the case. The property fails
+/// if it returns an error or panics, in which case the value is repeatedly replaced with the first
+/// of its [`Shrink`] candidates which still fails, until none of them do.
+///
+/// # Panics
+///
+/// Panics with the seed, the error and the minimal counter-example if the property fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::{bail, gen};
+///
+/// gen::check(
+///     100,
+///     |rng| (0..rng.below(20)).map(|_| rng.below(100) as u32).collect::<Vec<_>>(),
+///     |values| {
+///         let mut sorted = values.clone();
+///         sorted.sort();
+///
+///         if sorted.len() != values.len() {
+///             bail!("sorting lost values");
+///         }
+///
+///         Ok(())
+///     },
+/// );
+///
+/// let result = std::panic::catch_unwind(|| {
+///     gen::check(100, |rng| rng.below(1000), |&n| {
+///         if n >= 10 {
+///             bail!("too large");
+///         }
+///
+///         Ok(())
+///     })
+/// });
+///
+/// let message = result.unwrap_err().downcast::<String>().unwrap();
+/// assert!(message.ends_with("minimal counter-example: 10"));
+/// ```
+pub fn check<T, G, P>(cases: u64, mut generate: G, mut property: P)
+where
+    T: Shrink + fmt::Debug,
+    G: FnMut(&mut Rng) -> T,
+    P: FnMut(&T) -> Result<(), Error>,
+{
+    for seed in 0..cases {
+        let mut value = generate(&mut Rng::new(seed));
+
+        let mut error = match test(&mut property, &value) {
+            Ok(()) => continue,
+            Err(e) => e,
+        };
+
+        'shrink: loop {
+            for candidate in value.shrink() {
+                if let Err(e) = test(&mut property, &candidate) {
+                    value = candidate;
+                    error = e;
+                    continue 'shrink;
+                }
+            }
+
+            break;
+        }
+
+        panic!(
+            "property failed for seed {}: {}\nminimal counter-example: {:?}",
+            seed, error, value
+        );
+    }
+
+    fn test<T, P>(property: &mut P, value: &T) -> Result<(), Error>
+    where
+        P: FnMut(&T) -> Result<(), Error>,
+    {
+        match panic::catch_unwind(AssertUnwindSafe(|| property(value))) {
+            Ok(result) => result,
+            Err(panic) => Err(Error::msg(format!("panicked: {}", panic_message(&*panic)))),
+        }
+    }
+
+    fn panic_message(panic: &(dyn Any + Send)) -> &str {
+        if let Some(s) = panic.downcast_ref::<&str>() {
+            return s;
+        }
+
+        if let Some(s) = panic.downcast_ref::<String>() {
+            return s;
+        }
+
+        "unknown panic"
+    }
+}
+
+/// A value which can be shrunk into simpler values, used by [`check`] to find minimal
+/// counter-examples.
+pub trait Shrink: Sized {
+    /// Candidates which are simpler than this value, simplest first.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! shrink_unsigned {
+    ($($ty:ty),*) => {
+        $(
+        impl Shrink for $ty {
+            fn shrink(&self) -> Vec<Self> {
+                let n = *self;
+                let mut out = Vec::new();
+
+                for candidate in [0, n / 2, n.saturating_sub(1)].iter().cloned() {
+                    if candidate < n && !out.contains(&candidate) {
+                        out.push(candidate);
+                    }
+                }
+
+                out
+            }
+        }
+        )*
+    }
+}
+
+shrink_unsigned!(u8, u16, u32, u64, usize);
+
+impl Shrink for bool {
+    fn shrink(&self) -> Vec<Self> {
+        if *self {
+            vec![false]
+        } else {
+            vec![]
+        }
+    }
+}
+
+/// Characters are kept as they are, strings shrink by removing them.
+impl Shrink for char {
+    fn shrink(&self) -> Vec<Self> {
+        vec![]
+    }
+}
+
+impl Shrink for String {
+    fn shrink(&self) -> Vec<Self> {
+        let chars = self.chars().collect::<Vec<_>>();
+        chars
+            .shrink()
+            .into_iter()
+            .map(|c| c.into_iter().collect())
+            .collect()
+    }
+}
+
+/// Vectors shrink by removing ever smaller chunks of elements, and then by shrinking each element.
+impl<T> Shrink for Vec<T>
+where
+    T: Shrink + Clone,
+{
+    fn shrink(&self) -> Vec<Self> {
+        let mut out = Vec::new();
+        let mut chunk = self.len();
+
+        while chunk > 0 {
+            for start in (0..=self.len() - chunk).step_by(chunk) {
+                let mut candidate = self[..start].to_vec();
+                candidate.extend_from_slice(&self[start + chunk..]);
+                out.push(candidate);
+            }
+
+            chunk /= 2;
+        }
+
+        for (i, value) in self.iter().enumerate() {
+            for value in value.shrink() {
+                let mut candidate = self.clone();
+                candidate[i] = value;
+                out.push(candidate);
+            }
+        }
+
+        out
+    }
+}
+
+/// Maps shrink by removing entries, and then by shrinking each value.
+impl<K, V> Shrink for HashMap<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Shrink + Clone,
+{
+    fn shrink(&self) -> Vec<Self> {
+        let mut out = Vec::new();
+
+        for key in self.keys() {
+            let mut candidate = self.clone();
+            candidate.remove(key);
+            out.push(candidate);
+        }
+
+        for (key, value) in self {
+            for value in value.shrink() {
+                let mut candidate = self.clone();
+                candidate.insert(key.clone(), value);
+                out.push(candidate);
+            }
+        }
+
+        out
+    }
+}
+
+impl<A, B> Shrink for (A, B)
+where
+    A: Shrink + Clone,
+    B: Shrink + Clone,
+{
+    fn shrink(&self) -> Vec<Self> {
+        let mut out = Vec::new();
+        out.extend(self.0.shrink().into_iter().map(|a| (a, self.1.clone())));
+        out.extend(self.1.shrink().into_iter().map(|b| (self.0.clone(), b)));
+        out
+    }
+}
+
+impl<A, B, C> Shrink for (A, B, C)
+where
+    A: Shrink + Clone,
+    B: Shrink + Clone,
+    C: Shrink + Clone,
+{
+    fn shrink(&self) -> Vec<Self> {
+        let mut out = Vec::new();
+        let (a, b, c) = self;
+        out.extend(a.shrink().into_iter().map(|a| (a, b.clone(), c.clone())));
+        out.extend(b.shrink().into_iter().map(|b| (a.clone(), b, c.clone())));
+        out.extend(c.shrink().into_iter().map(|c| (a.clone(), b.clone(), c)));
+        out
+    }
+}
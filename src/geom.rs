@@ -0,0 +1,291 @@
+//! Positions and directions on a two-dimensional grid.
+//!
+//! Positions use the same orientation as [`Grid`](crate::Grid), with the origin in the top-left
+//! corner and `y` growing downwards. North is towards negative `y`.
+
+use crate::grid::{Coord, NEIGHBOURS4, NEIGHBOURS8};
+use crate::na;
+use std::cmp;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops;
+
+/// A position on a grid.
+///
+/// Positions are ordered in reading order, that is top-to-bottom and then left-to-right.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// let a = Pos::new(1, 2);
+/// let b = Pos::new(4, -2);
+///
+/// assert_eq!(a + b, Pos::new(5, 0));
+/// assert_eq!(b - a, Pos::new(3, -4));
+/// assert_eq!(-a * 2, Pos::new(-2, -4));
+/// assert_eq!((a.manhattan(b), a.chebyshev(b)), (7, 4));
+///
+/// assert_eq!(a.step(Dir::North), Pos::new(1, 1));
+/// assert_eq!(a.neighbours4().collect::<Vec<_>>(), vec![
+///     Pos::new(1, 1),
+///     Pos::new(0, 2),
+///     Pos::new(2, 2),
+///     Pos::new(1, 3),
+/// ]);
+///
+/// let mut positions = vec![Pos::new(2, 1), Pos::new(0, 2), Pos::new(1, 1)];
+/// positions.sort();
+/// assert_eq!(positions, vec![Pos::new(1, 1), Pos::new(2, 1), Pos::new(0, 2)]);
+///
+/// assert_eq!(Pos::from((3, 4)), Pos::new(3, 4));
+/// assert_eq!(Pos::new(3, 4).to_coord(), Some((3, 4)));
+/// assert_eq!(Pos::new(-1, 4).to_coord(), None);
+/// assert_eq!(na::Vector2::from(Pos::new(3, 4)), na::Vector2::new(3, 4));
+/// assert_eq!(a.to_string(), "1,2");
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pos {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Pos {
+    /// Construct a new position.
+    pub const fn new(x: i64, y: i64) -> Pos {
+        Pos { x, y }
+    }
+
+    /// The position one step away in the given direction.
+    pub fn step(self, dir: Dir) -> Pos {
+        self + dir.delta()
+    }
+
+    /// The Manhattan distance to another position, the sum of the distance along each axis.
+    pub fn manhattan(self, other: Pos) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// The Chebyshev distance to another position, the largest distance along either axis.
+    pub fn chebyshev(self, other: Pos) -> i64 {
+        i64::max((self.x - other.x).abs(), (self.y - other.y).abs())
+    }
+
+    /// The 4 orthogonal neighbours of the position, in reading order.
+    pub fn neighbours4(self) -> impl Iterator<Item = Pos> {
+        NEIGHBOURS4.iter().map(move |&d| self + Pos::from(d))
+    }
+
+    /// The 8 neighbours of the position including diagonals, in reading order.
+    pub fn neighbours8(self) -> impl Iterator<Item = Pos> {
+        NEIGHBOURS8.iter().map(move |&d| self + Pos::from(d))
+    }
+
+    /// The position as a [`Coord`] in a [`Grid`](crate::Grid), or `None` if either component is
+    /// negative.
+    pub fn to_coord(self) -> Option<Coord> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+}
+
+impl Ord for Pos {
+    fn cmp(&self, other: &Pos) -> cmp::Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for Pos {
+    fn partial_cmp(&self, other: &Pos) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ops::Add for Pos {
+    type Output = Pos;
+
+    fn add(self, other: Pos) -> Pos {
+        Pos::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl ops::AddAssign for Pos {
+    fn add_assign(&mut self, other: Pos) {
+        *self = *self + other;
+    }
+}
+
+impl ops::Sub for Pos {
+    type Output = Pos;
+
+    fn sub(self, other: Pos) -> Pos {
+        Pos::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl ops::SubAssign for Pos {
+    fn sub_assign(&mut self, other: Pos) {
+        *self = *self - other;
+    }
+}
+
+impl ops::Mul<i64> for Pos {
+    type Output = Pos;
+
+    fn mul(self, n: i64) -> Pos {
+        Pos::new(self.x * n, self.y * n)
+    }
+}
+
+impl ops::Neg for Pos {
+    type Output = Pos;
+
+    fn neg(self) -> Pos {
+        Pos::new(-self.x, -self.y)
+    }
+}
+
+impl From<(i64, i64)> for Pos {
+    fn from((x, y): (i64, i64)) -> Pos {
+        Pos::new(x, y)
+    }
+}
+
+impl From<Pos> for (i64, i64) {
+    fn from(pos: Pos) -> (i64, i64) {
+        (pos.x, pos.y)
+    }
+}
+
+impl From<na::Vector2<i64>> for Pos {
+    fn from(v: na::Vector2<i64>) -> Pos {
+        Pos::new(v.x, v.y)
+    }
+}
+
+impl From<Pos> for na::Vector2<i64> {
+    fn from(pos: Pos) -> na::Vector2<i64> {
+        na::Vector2::new(pos.x, pos.y)
+    }
+}
+
+impl fmt::Display for Pos {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{},{}", self.x, self.y)
+    }
+}
+
+/// One of the four directions on a grid.
+///
+/// Displayed as the first letter of the direction, like `N`.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// assert_eq!(Dir::North.turn_right(), Dir::East);
+/// assert_eq!(Dir::North.turn_left(), Dir::West);
+/// assert_eq!(Dir::East.reflect(), Dir::West);
+/// assert_eq!(Dir::South.delta(), Pos::new(0, 1));
+///
+/// let route = Dir::ALL.iter().map(|d| d.to_string()).collect::<String>();
+/// assert_eq!(route, "NESW");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Dir {
+    /// All directions, clockwise from north.
+    pub const ALL: [Dir; 4] = [Dir::North, Dir::East, Dir::South, Dir::West];
+
+    /// The change in position from taking one step in this direction.
+    pub fn delta(self) -> Pos {
+        match self {
+            Dir::North => Pos::new(0, -1),
+            Dir::East => Pos::new(1, 0),
+            Dir::South => Pos::new(0, 1),
+            Dir::West => Pos::new(-1, 0),
+        }
+    }
+
+    /// The direction after turning a quarter counter-clockwise.
+    pub fn turn_left(self) -> Dir {
+        match self {
+            Dir::North => Dir::West,
+            Dir::East => Dir::North,
+            Dir::South => Dir::East,
+            Dir::West => Dir::South,
+        }
+    }
+
+    /// The direction after turning a quarter clockwise.
+    pub fn turn_right(self) -> Dir {
+        match self {
+            Dir::North => Dir::East,
+            Dir::East => Dir::South,
+            Dir::South => Dir::West,
+            Dir::West => Dir::North,
+        }
+    }
+
+    /// The opposite direction.
+    pub fn reflect(self) -> Dir {
+        match self {
+            Dir::North => Dir::South,
+            Dir::East => Dir::West,
+            Dir::South => Dir::North,
+            Dir::West => Dir::East,
+        }
+    }
+}
+
+impl fmt::Display for Dir {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let n = match *self {
+            Dir::North => "N",
+            Dir::East => "E",
+            Dir::South => "S",
+            Dir::West => "W",
+        };
+
+        n.fmt(fmt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns_are_inverse() {
+        for dir in Dir::ALL.iter().cloned() {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.turn_left().turn_left(), dir.reflect());
+            assert_eq!(dir.turn_right().turn_right(), dir.reflect());
+            assert_eq!(dir.reflect().reflect(), dir);
+            assert_eq!(dir.reflect().delta(), -dir.delta());
+        }
+    }
+
+    #[test]
+    fn test_neighbours8_in_reading_order() {
+        let pos = Pos::new(3, -2);
+        let neighbours = pos.neighbours8().collect::<Vec<_>>();
+
+        let mut sorted = neighbours.clone();
+        sorted.sort();
+
+        assert_eq!(neighbours, sorted);
+        assert_eq!(neighbours.len(), 8);
+        assert!(neighbours.iter().all(|n| pos.chebyshev(*n) == 1));
+        assert_eq!(neighbours.first(), Some(&Pos::new(2, -3)));
+        assert_eq!(neighbours.last(), Some(&Pos::new(4, -1)));
+    }
+}
@@ -0,0 +1,403 @@
+use crate::{Error, ParseError, Pos};
+use std::fmt;
+use std::ops;
+
+/// A position in a grid, as `(x, y)`.
+pub type Coord = (usize, usize);
+
+/// Offsets to the 4 neighbours of a position, in reading order.
+pub(crate) const NEIGHBOURS4: [(i64, i64); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Offsets to the 8 neighbours of a position, in reading order.
+pub(crate) const NEIGHBOURS8: [(i64, i64); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A dense rectangular grid, stored in reading order.
+///
+/// Positions are `(x, y)` with the origin in the top-left corner. Lookups which might fall outside
+/// of the grid go through [`Grid::get`] or [`Grid::offset`], which return `None` instead of
+/// panicking.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// fn main() -> Result<(), Error> {
+///     let grid = Grid::parse("#..\n.#.\n", |_, c| Ok(c == '#'))?;
+///
+///     assert_eq!((grid.width(), grid.height()), (3, 2));
+///     assert_eq!(grid.get((1, 1)), Some(&true));
+///     assert_eq!(grid.get((3, 0)), None);
+///     assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+///
+///     let walls = grid.neighbours8((0, 1)).filter(|p| grid[*p]).collect::<Vec<_>>();
+///     assert_eq!(walls, vec![(0, 0), (1, 1)]);
+///
+///     let grid = grid.map(|_, wall| if *wall { '#' } else { '.' });
+///     assert_eq!(grid.to_string(), "#..\n.#.\n");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Construct a grid where every position has the given value.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            data: vec![value; width * height],
+        }
+    }
+
+    /// Construct a grid by calling the given function for every position, in reading order.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Coord) -> T) -> Grid<T> {
+        let mut data = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                data.push(f((x, y)));
+            }
+        }
+
+        Grid {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Parse a grid from a map of characters, with one row per line.
+    ///
+    /// Empty lines are skipped, and every other line must have the same length. Errors from the
+    /// given function are reported as a [`ParseError`] pointing at the offending character.
+    pub fn parse(
+        input: &str,
+        f: impl FnMut(Coord, char) -> Result<T, Error>,
+    ) -> Result<Grid<T>, Error> {
+        Grid::parse_rows(input, None, f)
+    }
+
+    /// Parse a grid like [`Grid::parse`], but pad lines which are shorter than the longest one with
+    /// the given value instead of failing.
+    ///
+    /// This accepts maps where trailing whitespace has been trimmed from some lines.
+    pub fn parse_padded(
+        input: &str,
+        pad: T,
+        f: impl FnMut(Coord, char) -> Result<T, Error>,
+    ) -> Result<Grid<T>, Error>
+    where
+        T: Clone,
+    {
+        Grid::parse_rows(input, Some(&mut || pad.clone()), f)
+    }
+
+    fn parse_rows(
+        input: &str,
+        mut pad: Option<&mut dyn FnMut() -> T>,
+        mut f: impl FnMut(Coord, char) -> Result<T, Error>,
+    ) -> Result<Grid<T>, Error> {
+        let mut width = match pad {
+            Some(_) => input.lines().map(|line| line.chars().count()).max(),
+            None => None,
+        };
+
+        let mut height = 0;
+        let mut data = Vec::new();
+
+        for (n, line) in input.lines().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut x = 0;
+
+            for (i, c) in line.char_indices() {
+                match f((x, height), c) {
+                    Ok(value) => data.push(value),
+                    Err(e) => {
+                        let span = i..i + c.len_utf8();
+                        return Err(ParseError::new(n + 1, line, span, e).into());
+                    }
+                }
+
+                x += 1;
+            }
+
+            if let (Some(pad), Some(width)) = (pad.as_mut(), width) {
+                for _ in x..width {
+                    data.push(pad());
+                }
+
+                x = width;
+            }
+
+            match width {
+                Some(width) if width != x => {
+                    let message = format!("expected {} columns, but got {}", width, x);
+                    return Err(
+                        ParseError::new(n + 1, line, line.len()..line.len(), message).into(),
+                    );
+                }
+                _ => width = Some(x),
+            }
+
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or_default(),
+            height,
+            data,
+        })
+    }
+
+    /// The width of the grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the grid.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Test if the given position is inside of the grid.
+    pub fn contains(&self, (x, y): Coord) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Get the value at the given position, or `None` if it is out of bounds.
+    pub fn get(&self, pos: Coord) -> Option<&T> {
+        if !self.contains(pos) {
+            return None;
+        }
+
+        self.data.get(pos.1 * self.width + pos.0)
+    }
+
+    /// Get the value at the given [`Pos`], or `None` if it is out of bounds.
+    pub fn get_pos(&self, pos: Pos) -> Option<&T> {
+        self.get(pos.to_coord()?)
+    }
+
+    /// Get the value at the given position mutably, or `None` if it is out of bounds.
+    pub fn get_mut(&mut self, pos: Coord) -> Option<&mut T> {
+        if !self.contains(pos) {
+            return None;
+        }
+
+        self.data.get_mut(pos.1 * self.width + pos.0)
+    }
+
+    /// Set the value at the given position, returning the old value.
+    ///
+    /// Returns `None` without changing anything if the position is out of bounds.
+    pub fn replace(&mut self, pos: Coord, value: T) -> Option<T> {
+        self.get_mut(pos).map(|v| std::mem::replace(v, value))
+    }
+
+    /// Offset the given position, returning `None` if the result is outside of the grid.
+    pub fn offset(&self, (x, y): Coord, (dx, dy): (i64, i64)) -> Option<Coord> {
+        let x = (x as i64).checked_add(dx)?;
+        let y = (y as i64).checked_add(dy)?;
+
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        let pos = (x as usize, y as usize);
+
+        if !self.contains(pos) {
+            return None;
+        }
+
+        Some(pos)
+    }
+
+    /// Iterate over the up to 4 orthogonal neighbours inside the grid, in reading order.
+    pub fn neighbours4(&self, pos: Coord) -> impl Iterator<Item = Coord> + '_ {
+        NEIGHBOURS4.iter().filter_map(move |d| self.offset(pos, *d))
+    }
+
+    /// Iterate over the up to 8 neighbours inside the grid, including diagonals, in reading order.
+    pub fn neighbours8(&self, pos: Coord) -> impl Iterator<Item = Coord> + '_ {
+        NEIGHBOURS8.iter().filter_map(move |d| self.offset(pos, *d))
+    }
+
+    /// Iterate over all positions in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// Iterate over all values in reading order.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    /// Iterate over all positions and their values in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.positions().zip(self.data.iter())
+    }
+
+    /// Iterate mutably over all positions and their values in reading order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Coord, &mut T)> {
+        self.positions().zip(self.data.iter_mut())
+    }
+
+    /// Construct a new grid of the same size by mapping every value.
+    pub fn map<U>(&self, mut f: impl FnMut(Coord, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            data: self.iter().map(|(pos, v)| f(pos, v)).collect(),
+        }
+    }
+}
+
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Grid {
+            width: 0,
+            height: 0,
+            data: Vec::new(),
+        }
+    }
+}
+
+impl<T> ops::Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Coord) -> &T {
+        match self.get(pos) {
+            Some(value) => value,
+            None => panic!(
+                "position {:?} out of bounds for {}x{} grid",
+                pos, self.width, self.height
+            ),
+        }
+    }
+}
+
+impl<T> ops::IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, pos: Coord) -> &mut T {
+        let (width, height) = (self.width, self.height);
+
+        match self.get_mut(pos) {
+            Some(value) => value,
+            None => panic!(
+                "position {:?} out of bounds for {}x{} grid",
+                pos, width, height
+            ),
+        }
+    }
+}
+
+impl<T> fmt::Display for Grid<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.data.chunks(self.width.max(1)) {
+            for value in row {
+                value.fmt(fmt)?;
+            }
+
+            writeln!(fmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_err;
+
+    #[test]
+    fn test_parse_ragged() {
+        let e = Grid::parse("###\n#.\n###\n", |_, c| Ok(c)).unwrap_err();
+        let e = e.downcast::<ParseError>().expect("parse error");
+
+        assert_eq!((e.line(), e.columns()), (2, 3..3));
+        assert_eq!(e.message(), "expected 3 columns, but got 2");
+    }
+
+    #[test]
+    fn test_parse_padded() -> Result<(), Error> {
+        let grid = Grid::parse_padded("###\n#\n\n##\n", '.', |_, c| Ok(c))?;
+
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        let rows = grid.values().collect::<String>();
+        assert_eq!(rows, "####..##.");
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_pos() -> Result<(), Error> {
+        let grid = Grid::parse("ab\ncd\n", |_, c| Ok(c))?;
+
+        assert_eq!(grid.get_pos(Pos::new(1, 1)), Some(&'d'));
+        assert_eq!(grid.get_pos(Pos::new(-1, 0)), None);
+        assert_eq!(grid.get_pos(Pos::new(2, 0)), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_empty() -> Result<(), Error> {
+        for input in ["", "\n", "\n\n"].iter() {
+            let grid = Grid::parse(input, |_, c| Ok(c))?;
+            assert_eq!((grid.width(), grid.height()), (0, 0));
+            assert_eq!(grid.values().count(), 0);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_origin() -> Result<(), Error> {
+        let mut seen = Vec::new();
+
+        // NB: skipped lines don't count towards the row, but do towards the line of errors.
+        let grid = Grid::parse("\nab\n\ncd\n", |pos, c| {
+            seen.push((pos, c));
+            Ok(c)
+        })?;
+
+        assert_eq!(
+            seen,
+            vec![((0, 0), 'a'), ((1, 0), 'b'), ((0, 1), 'c'), ((1, 1), 'd')]
+        );
+        assert_eq!(grid[(1, 1)], 'd');
+
+        let e = Grid::parse("\nab\n\ncx\n", |_, c| match c {
+            'x' => Err(format_err!("bad square")),
+            c => Ok(c),
+        })
+        .unwrap_err();
+
+        let e = e.downcast::<ParseError>().expect("parse error");
+        assert_eq!((e.line(), e.columns()), (4, 2..3));
+        Ok(())
+    }
+}
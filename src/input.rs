@@ -0,0 +1,165 @@
+use crate::{format_err, Context, Error};
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Environment variable consulted for an input path if none is provided on the command line.
+pub const INPUT_ENV: &str = "AOC_INPUT";
+
+/// Where an input was loaded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Input bundled with the crate through `input_str!`.
+    Bundled,
+    /// Input loaded from a file at runtime.
+    Path(PathBuf),
+    /// Input read from stdin.
+    Stdin,
+    /// Input generated from a seed, see [`gen`](crate::gen).
+    Generated { seed: u64, size: usize },
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Source::Bundled => "bundled".fmt(fmt),
+            Source::Path(ref path) => path.display().fmt(fmt),
+            Source::Stdin => "<stdin>".fmt(fmt),
+            Source::Generated { seed, size } => {
+                write!(fmt, "generated with seed {} and size {}", seed, size)
+            }
+        }
+    }
+}
+
+/// Puzzle input which is either bundled with the crate, or loaded at runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// fn main() -> Result<(), Error> {
+///     let input = Input::bundled("day1.txt", "+1\n-2\n");
+///     assert!(input.is_bundled());
+///     assert_eq!(columns!(input.reader(), char::is_whitespace, i64), vec![1, -2]);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Input {
+    name: String,
+    data: Cow<'static, str>,
+    source: Source,
+}
+
+impl Input {
+    /// Construct an input from data bundled with the crate.
+    pub fn bundled(name: &str, data: &'static str) -> Input {
+        Input {
+            name: name.to_string(),
+            data: Cow::Borrowed(data),
+            source: Source::Bundled,
+        }
+    }
+
+    /// Construct an input which was generated from the given seed and size.
+    pub fn generated(seed: u64, size: usize, data: String) -> Input {
+        Input {
+            name: format!("gen-{}-{}", seed, size),
+            data: Cow::Owned(data),
+            source: Source::Generated { seed, size },
+        }
+    }
+
+    /// Load input from the given path.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Input, Error> {
+        let path = path.as_ref();
+
+        let data = fs::read_to_string(path)
+            .with_context(|| format!("failed to read input: {}", path.display()))?;
+
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .ok_or_else(|| format_err!("input path has no file name: {}", path.display()))?;
+
+        Ok(Input {
+            name,
+            data: Cow::Owned(data),
+            source: Source::Path(path.to_owned()),
+        })
+    }
+
+    /// Read the entire input from stdin.
+    pub fn from_stdin() -> Result<Input, Error> {
+        let mut data = String::new();
+        io::stdin()
+            .read_to_string(&mut data)
+            .context("failed to read input from stdin")?;
+
+        Ok(Input {
+            name: String::from("<stdin>"),
+            data: Cow::Owned(data),
+            source: Source::Stdin,
+        })
+    }
+
+    /// Resolve the input to use for a puzzle.
+    ///
+    /// `path` is typically the first command line argument, where `-` means to read from stdin.
+    /// If it's not provided, the `AOC_INPUT` environment variable is consulted in the same
+    /// manner. If neither is set, the bundled input is used.
+    pub fn resolve(
+        path: Option<&OsStr>,
+        name: &str,
+        bundled: &'static str,
+    ) -> Result<Input, Error> {
+        let env = std::env::var_os(INPUT_ENV);
+
+        match path.or(env.as_deref()) {
+            Some(path) if path == "-" => Self::from_stdin(),
+            Some(path) => Self::from_path(path),
+            None => Ok(Self::bundled(name, bundled)),
+        }
+    }
+
+    /// The name of the input.
+    ///
+    /// For bundled inputs this is the name of the file in the `input` directory.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Where the input was loaded from.
+    pub fn source(&self) -> &Source {
+        &self.source
+    }
+
+    /// Test if this is the input bundled with the crate.
+    pub fn is_bundled(&self) -> bool {
+        self.source == Source::Bundled
+    }
+
+    /// Access the input as a string.
+    pub fn as_str(&self) -> &str {
+        &self.data
+    }
+
+    /// A fingerprint of the contents of the input, used to identify it regardless of its name.
+    ///
+    /// This is a 64-bit FNV-1a hash, which is stable across builds and platforms.
+    pub fn fingerprint(&self) -> u64 {
+        self.data.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    /// Get a reader over the input, suitable for use with `lines!` and `columns!`.
+    pub fn reader(&self) -> io::Cursor<&[u8]> {
+        io::Cursor::new(self.data.as_bytes())
+    }
+}
@@ -1,5 +1,5 @@
+pub use anyhow::{bail, format_err, Context, Error};
 pub use chrono::Timelike;
-pub use anyhow::{bail, format_err, Error, Context};
 pub use hashbrown::*;
 pub use itertools::Itertools;
 pub use nalgebra as na;
@@ -9,6 +9,28 @@ pub use std::io::{BufRead, BufReader};
 use std::ops;
 pub use std::str;
 
+pub mod answers;
+pub mod bench;
+pub mod cycle;
+pub mod days;
+mod error;
+pub mod events;
+pub mod export;
+pub mod gen;
+mod geom;
+mod grid;
+mod input;
+pub mod search;
+mod solution;
+pub mod visuals;
+
+pub use self::bench::Variant;
+pub use self::error::{ParseError, ValidationError};
+pub use self::geom::{Dir, Pos};
+pub use self::grid::{Coord, Grid};
+pub use self::input::{Input, Source};
+pub use self::solution::{solve, Answer, Day, Parsed, Part, Solution};
+
 /// Get the input as a string.
 #[macro_export]
 macro_rules! input_str {
@@ -25,8 +47,28 @@ macro_rules! input {
     };
 }
 
+/// Load the main input for a puzzle at runtime.
+///
+/// The input is loaded from the path given as the first command line argument, or the `AOC_INPUT`
+/// environment variable. A path of `-` reads the input from stdin. If neither is specified, the
+/// bundled input with the given name is used.
+///
+/// See [`Input::resolve`].
+#[macro_export]
+macro_rules! load_input {
+    ($name:expr) => {
+        $crate::Input::resolve(
+            std::env::args_os().nth(1).as_deref(),
+            $name,
+            $crate::input_str!($name),
+        )
+    };
+}
+
 /// Read input as a long set of columns.
 ///
+/// Values which can't be parsed are reported as a [`ParseError`].
+///
 /// # Examples
 ///
 /// Parsing different lines in different ways:
@@ -47,18 +89,14 @@ macro_rules! columns {
 
         let mut buf = String::new();
         $data.read_to_string(&mut buf)?;
-        buf.trim()
-            .split($sep)
-            .filter(|s| !s.is_empty())
-            .map(str::parse)
-            .collect::<Result<Vec<$ty>, _>>()?
+        $crate::parse_columns::<$ty>(stringify!($ty), &buf, $sep)?
     }};
 }
 
 /// Read and parse lines.
 ///
 /// Builds an iterator out of the given specification that will attempt to parse columns from each
-/// line and provide excellent diagnostics in case it can't.
+/// line and provide excellent diagnostics in case it can't, in the form of a [`ParseError`].
 ///
 /// # Examples
 ///
@@ -78,26 +116,36 @@ macro_rules! columns {
 ///         assert_eq!(line?, (String::from("foo"), 4));
 ///     }
 ///
+///     let mut d = std::io::Cursor::new("1 2\n3 x");
+///     let e = lines!(&mut d, u32, u32).nth(1).unwrap().unwrap_err();
+///     assert_eq!((e.line(), e.columns()), (2, 3..4));
+///     assert_eq!(e.message(), "bad `u32`: bad value `x`: invalid digit found in string");
+///
 ///     Ok(())
 /// }
 /// ```
 #[macro_export]
 macro_rules! lines {
     ($data:expr, $($ty:ty),*) => {{
-        struct Iter<R, N> {
+        struct Iter<R> {
             data: R,
-            line: N,
+            line: usize,
             buf: String,
         }
 
-        impl<R, N> Iterator for Iter<R, N> where R: std::io::BufRead, N: Iterator<Item = usize> {
-            type Item = Result<($($ty,)*), Error>;
+        impl<R> Iterator for Iter<R> where R: std::io::BufRead {
+            type Item = Result<($($ty,)*), $crate::ParseError>;
 
             fn next(&mut self) -> Option<Self::Item> {
                 self.buf.clear();
+                self.line += 1;
+                let line = self.line;
 
                 let size = match self.data.read_line(&mut self.buf) {
-                    Err(e) => return Some(Err(Error::from(e))),
+                    Err(e) => {
+                        let message = format!("failed to read line: {}", e);
+                        return Some(Err($crate::ParseError::new(line, "", 0..0, message)));
+                    }
                     Ok(size) => size,
                 };
 
@@ -105,17 +153,10 @@ macro_rules! lines {
                     return None;
                 }
 
-                let mut cols = self.buf.split_whitespace();
-
-                let line = match self.line.next() {
-                    None => return Some(Err(format_err!("no more lines"))),
-                    Some(line) => line,
-                };
-
-                let mut col = 1..;
+                let mut cols = $crate::split_columns(&self.buf);
 
                 let out = ($({
-                    match $crate::parse_column(stringify!($ty), line, &mut cols, &mut col) {
+                    match $crate::parse_column(stringify!($ty), line, &self.buf, &mut cols) {
                         Err(e) => return Some(Err(e)),
                         Ok(d) => d,
                     }
@@ -127,35 +168,259 @@ macro_rules! lines {
 
         Iter {
             data: $data,
-            line: 1..,
+            line: 0,
             buf: String::new(),
         }
     }}
 }
 
+/// Split a line into whitespace-separated columns, with the byte range of each column.
+pub fn split_columns(line: &str) -> impl Iterator<Item = (ops::Range<usize>, &str)> {
+    line.split_whitespace().map(move |col| {
+        let start = col.as_ptr() as usize - line.as_ptr() as usize;
+        (start..start + col.len(), col)
+    })
+}
+
 /// Parse a single column with the given type.
+///
+/// `text` is the line being parsed, and `it` the remaining columns in it, as produced by
+/// [`split_columns`].
 pub fn parse_column<'a, T>(
     ty: &'static str,
     line: usize,
-    mut it: impl Iterator<Item = &'a str>,
-    mut col: impl Iterator<Item = usize>,
-) -> Result<T, Error>
+    text: &str,
+    mut it: impl Iterator<Item = (ops::Range<usize>, &'a str)>,
+) -> Result<T, ParseError>
 where
     T: std::str::FromStr,
     T::Err: std::fmt::Display,
 {
-    let col = match col.next() {
+    let (span, col) = match it.next() {
         Some(col) => col,
-        None => bail!("no more columns"),
+        None => {
+            let end = text.trim_end().len();
+            let message = format!("bad `{}`: missing column", ty);
+            return Err(ParseError::new(line, text, end..end, message));
+        }
     };
 
-    let d = it
-        .next()
-        .ok_or_else(|| format!("missing column"))
-        .and_then(|p| str::parse::<T>(p).map_err(|e| format!("bad value `{}`: {}", p, e)))
-        .map_err(|e| format_err!("bad `{}` on {}:{}: {}", ty, line, col, e))?;
+    str::parse::<T>(col).map_err(|e| {
+        let message = format!("bad `{}`: bad value `{}`: {}", ty, col, e);
+        ParseError::new(line, text, span, message)
+    })
+}
+
+/// Parse all columns in the input separated by `sep`, as used by `columns!`.
+pub fn parse_columns<T>(
+    ty: &'static str,
+    input: &str,
+    sep: impl FnMut(char) -> bool,
+) -> Result<Vec<T>, ParseError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let mut out = Vec::new();
+
+    for col in input.trim().split(sep).filter(|s| !s.is_empty()) {
+        match str::parse::<T>(col) {
+            Ok(value) => out.push(value),
+            Err(e) => {
+                let start = col.as_ptr() as usize - input.as_ptr() as usize;
+                let message = format!("bad `{}`: bad value `{}`: {}", ty, col, e);
+                return Err(ParseError::locate(input, start..start + col.len(), message));
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Scan lines against a template, parsing each `{}` capture into the given types.
+///
+/// Literal text in the template must match exactly, and each capture extends up to the first
+/// occurrence of the literal text that follows it. The text after the last capture must end the
+/// line. Captures are trimmed of surrounding whitespace before they are parsed.
+///
+/// With `line = <n>`, a single line is scanned and a tuple of the captures is returned. Otherwise
+/// an iterator is returned which scans every non-empty line of the input. Input which doesn't match
+/// is reported as a [`ParseError`].
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// fn main() -> Result<(), Error> {
+///     let claim = "#1 @ 1,3: 4x4";
+///     let claim = scan!(line = 1, claim, "#{} @ {},{}: {}x{}", u32, u32, u32, u32, u32)?;
+///     assert_eq!(claim, (1, 1, 3, 4, 4));
+///
+///     let input = "position=< 9,  1> velocity=< 0,  2>\nposition=<-3, 11> velocity=< 1, -2>\n";
+///
+///     let points = scan!(input, "position=<{},{}> velocity=<{},{}>", i32, i32, i32, i32)
+///         .collect::<Result<Vec<_>, Error>>()?;
+///
+///     assert_eq!(points, vec![(9, 1, 0, 2), (-3, 11, 1, -2)]);
+///
+///     let e = scan!(input, "position=<{},{}> velocity=<{},{}>", u32, i32, i32, i32)
+///         .collect::<Result<Vec<_>, Error>>()
+///         .unwrap_err();
+///
+///     let e = e.downcast::<ParseError>()?;
+///     assert_eq!((e.line(), e.columns()), (2, 11..13));
+///     assert_eq!(e.message(), "bad `u32`: bad value `-3`: invalid digit found in string");
+///     Ok(())
+/// }
+/// ```
+#[macro_export]
+macro_rules! scan {
+    (line = $line:expr, $input:expr, $template:expr, $($ty:ty),+ $(,)?) => {{
+        (|| -> Result<($($ty,)+), $crate::Error> {
+            let line: usize = $line;
+            let text: &str = $input;
+            let types = [$(stringify!($ty),)+];
+            let captures = $crate::scan_captures($template, types.len(), line, text)?;
+            let mut it = types.iter().zip(captures);
+
+            Ok(($({
+                let (ty, (span, capture)) = it.next().expect("capture for every type");
+                $crate::parse_capture::<$ty>(ty, line, text, span, capture)?
+            },)+))
+        })()
+    }};
+
+    ($input:expr, $template:expr, $($ty:ty),+ $(,)?) => {{
+        let input: &str = $input;
 
-    Ok(d)
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(move |(n, line)| $crate::scan!(line = n + 1, line, $template, $($ty),+))
+    }};
+}
+
+/// Match a single line against a `scan!` template, returning the span and text of each capture.
+///
+/// Input which doesn't match the template is reported as a [`ParseError`].
+pub fn scan_captures<'a>(
+    template: &str,
+    expected: usize,
+    line: usize,
+    input: &'a str,
+) -> Result<Vec<(ops::Range<usize>, &'a str)>, Error> {
+    let mut literals = template.split("{}");
+    let count = literals.clone().count() - 1;
+
+    if count != expected {
+        bail!(
+            "template `{}` has {} captures, but {} types were given",
+            template,
+            count,
+            expected
+        );
+    }
+
+    let first = literals.next().unwrap_or_default();
+
+    if !input.starts_with(first) {
+        let message = format!("expected `{}`", first);
+        return Err(ParseError::new(line, input, 0..0, message).into());
+    }
+
+    let mut pos = first.len();
+    let mut captures = Vec::with_capacity(count);
+    let mut literals = literals.peekable();
+
+    while let Some(literal) = literals.next() {
+        let rest = &input[pos..];
+
+        let len = if literals.peek().is_none() {
+            if !rest.ends_with(literal) {
+                let message = format!("expected line to end with `{}`", literal);
+                return Err(ParseError::new(line, input, input.len()..input.len(), message).into());
+            }
+
+            rest.len() - literal.len()
+        } else {
+            if literal.is_empty() {
+                bail!("template `{}` has adjacent captures", template);
+            }
+
+            match rest.find(literal) {
+                Some(len) => len,
+                None => {
+                    let message = format!("expected `{}`", literal);
+                    return Err(ParseError::new(line, input, pos..pos, message).into());
+                }
+            }
+        };
+
+        let capture = &rest[..len];
+        let trimmed = capture.trim();
+        let start = pos + capture.len() - capture.trim_start().len();
+
+        captures.push((start..start + trimmed.len(), trimmed));
+        pos += len + literal.len();
+    }
+
+    Ok(captures)
+}
+
+/// Fill in the `{}` captures of a [`scan!`] template with the given values, which is the inverse of
+/// scanning it.
+///
+/// # Panics
+///
+/// Panics if the number of values doesn't match the number of captures in the template.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// fn main() -> Result<(), Error> {
+///     const CLAIM: &str = "#{} @ {},{}: {}x{}";
+///
+///     let claim = fill(CLAIM, &[&1, &1, &3, &4, &4]);
+///     assert_eq!(claim, "#1 @ 1,3: 4x4");
+///     assert_eq!(scan!(line = 1, &claim, CLAIM, u32, u32, u32, u32, u32)?, (1, 1, 3, 4, 4));
+///     Ok(())
+/// }
+/// ```
+pub fn fill(template: &str, values: &[&dyn std::fmt::Display]) -> String {
+    let mut literals = template.split("{}");
+    let mut out = String::from(literals.next().unwrap_or_default());
+    let mut values = values.iter();
+
+    for literal in literals {
+        let value = values.next().expect("a value for every capture");
+        out.push_str(&value.to_string());
+        out.push_str(literal);
+    }
+
+    assert!(values.next().is_none(), "more values than captures");
+    out
+}
+
+/// Parse a single capture from `scan!` with the given type.
+pub fn parse_capture<T>(
+    ty: &str,
+    line: usize,
+    text: &str,
+    span: ops::Range<usize>,
+    capture: &str,
+) -> Result<T, ParseError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    str::parse::<T>(capture).map_err(|e| {
+        let message = format!("bad `{}`: bad value `{}`: {}", ty, capture, e);
+        ParseError::new(line, text, span, message)
+    })
 }
 
 /// Ignore input.
@@ -198,7 +463,7 @@ macro_rules! tuple {
             type Err = Error;
 
             fn from_str(s: &str) -> Result<Self, Self::Err> {
-                let mut it = s.split(|c| !char::is_numeric(c)).filter(|s| s.trim() != "");
+                let mut it = $crate::numbers(s);
 
                 Ok($name($({
                     let x = it.next().ok_or_else(|| format_err!("expected x"))?;
@@ -209,6 +474,56 @@ macro_rules! tuple {
     }
 }
 
+/// Find all numbers in the given string, keeping the sign of negative numbers.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+///
+/// let numbers = aoc2018::numbers("x=-1, y=2..-3 1-2").collect::<Vec<_>>();
+/// assert_eq!(numbers, vec!["-1", "2", "-3", "1", "2"]);
+///
+/// let Pair(x, y) = str::parse::<Pair<i32, i32>>("<-3, 4>").unwrap();
+/// assert_eq!((x, y), (-3, 4));
+/// ```
+pub fn numbers(s: &str) -> impl Iterator<Item = &str> {
+    let mut it = s.char_indices().peekable();
+    let mut prev = None;
+
+    std::iter::from_fn(move || loop {
+        let (start, c) = it.next()?;
+
+        // NB: a dash is only a sign if it's not between two numbers.
+        let is_sign = c == '-'
+            && !prev.map(char::is_numeric).unwrap_or_default()
+            && it
+                .peek()
+                .map(|&(_, c)| char::is_numeric(c))
+                .unwrap_or_default();
+
+        prev = Some(c);
+
+        if !is_sign && !char::is_numeric(c) {
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+
+        while let Some(&(i, c)) = it.peek() {
+            if !char::is_numeric(c) {
+                break;
+            }
+
+            end = i + c.len_utf8();
+            prev = Some(c);
+            it.next();
+        }
+
+        return Some(&s[start..end]);
+    })
+}
+
 // Decoded a pair of values.
 tuple!(Pair, A, B);
 // Decodes a triple of values.
@@ -259,7 +574,7 @@ where
 
     /// Get a copy of the current min/max value.
     pub fn get(&self) -> Option<(Idx, Idx)> {
-        self.value.clone()
+        self.value
     }
 
     /// Calculate the delta if the stored value is `Sub<Idx>`.
@@ -267,7 +582,7 @@ where
     where
         Idx: ops::Sub<Idx, Output = Idx>,
     {
-        self.value.clone().map(|(mn, mx)| mx - mn)
+        self.value.map(|(mn, mx)| mx - mn)
     }
 
     /// Calculates an inclusive range if there is one.
@@ -277,8 +592,139 @@ where
     /// Panics if there is no samples.
     pub fn range_inclusive(&self) -> ops::RangeInclusive<Idx> {
         self.value
-            .clone()
             .map(|(mn, mx)| ops::RangeInclusive::new(mn, mx))
             .expect("no samples")
     }
 }
+
+/// A two-dimensional bounding box, which tracks the minimum and maximum of each axis.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::Bounds2;
+///
+/// let mut bounds = vec![(1, 2), (3, 1)].into_iter().collect::<Bounds2<i32>>();
+///
+/// assert_eq!(bounds.get(), Some(((1, 1), (3, 2))));
+/// assert_eq!((bounds.width(), bounds.height()), (Some(3), Some(2)));
+/// assert!(bounds.contains((2, 2)));
+/// assert!(!bounds.contains((0, 2)));
+///
+/// bounds.sample((2, 3));
+/// assert_eq!(bounds.height(), Some(3));
+///
+/// let bounds = bounds.grow(1);
+/// assert_eq!(bounds.get(), Some(((0, 0), (4, 4))));
+///
+/// let small = vec![(0, 0), (1, 1)].into_iter().collect::<Bounds2<i32>>();
+/// assert_eq!(small.iter().collect::<Vec<_>>(), vec![(0, 0), (1, 0), (0, 1), (1, 1)]);
+/// assert_eq!(Bounds2::<i32>::default().iter().count(), 0);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Bounds2<Idx> {
+    x: MinMax<Idx>,
+    y: MinMax<Idx>,
+}
+
+impl<Idx> Bounds2<Idx>
+where
+    Idx: Copy + PartialOrd,
+{
+    /// Sample a point to include in the bounds.
+    pub fn sample(&mut self, (x, y): (Idx, Idx)) {
+        self.x.sample(x);
+        self.y.sample(y);
+    }
+
+    /// Test if no points have been sampled.
+    pub fn is_empty(&self) -> bool {
+        self.x.get().is_none()
+    }
+
+    /// Get the minimum and the maximum corner of the bounds.
+    pub fn get(&self) -> Option<((Idx, Idx), (Idx, Idx))> {
+        let (x0, x1) = self.x.get()?;
+        let (y0, y1) = self.y.get()?;
+        Some(((x0, y0), (x1, y1)))
+    }
+
+    /// The bounds of the x axis.
+    pub fn x(&self) -> &MinMax<Idx> {
+        &self.x
+    }
+
+    /// The bounds of the y axis.
+    pub fn y(&self) -> &MinMax<Idx> {
+        &self.y
+    }
+
+    /// The number of columns covered by the bounds.
+    pub fn width(&self) -> Option<Idx>
+    where
+        Idx: ops::Sub<Output = Idx> + ops::Add<Output = Idx> + num::One,
+    {
+        self.x.delta().map(|d| d + Idx::one())
+    }
+
+    /// The number of rows covered by the bounds.
+    pub fn height(&self) -> Option<Idx>
+    where
+        Idx: ops::Sub<Output = Idx> + ops::Add<Output = Idx> + num::One,
+    {
+        self.y.delta().map(|d| d + Idx::one())
+    }
+
+    /// Test if the given point is inside of the bounds.
+    pub fn contains(&self, (x, y): (Idx, Idx)) -> bool {
+        match self.get() {
+            Some(((x0, y0), (x1, y1))) => x0 <= x && x <= x1 && y0 <= y && y <= y1,
+            None => false,
+        }
+    }
+
+    /// Grow the bounds by the given margin in every direction.
+    pub fn grow(&self, margin: Idx) -> Self
+    where
+        Idx: ops::Sub<Output = Idx> + ops::Add<Output = Idx> + Default,
+    {
+        let mut bounds = Self::default();
+
+        if let Some(((x0, y0), (x1, y1))) = self.get() {
+            bounds.sample((x0 - margin, y0 - margin));
+            bounds.sample((x1 + margin, y1 + margin));
+        }
+
+        bounds
+    }
+
+    /// Iterate over all points in the bounds in reading order, that is row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Idx, Idx)>
+    where
+        ops::RangeInclusive<Idx>: Iterator<Item = Idx>,
+    {
+        let ranges = self.get().map(|((x0, y0), (x1, y1))| (x0..=x1, y0..=y1));
+
+        ranges
+            .into_iter()
+            .flat_map(|(xs, ys)| ys.flat_map(move |y| xs.clone().map(move |x| (x, y))))
+    }
+}
+
+impl<Idx> std::iter::FromIterator<(Idx, Idx)> for Bounds2<Idx>
+where
+    Idx: Copy + PartialOrd + Default,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (Idx, Idx)>,
+    {
+        let mut bounds = Self::default();
+
+        for point in iter {
+            bounds.sample(point);
+        }
+
+        bounds
+    }
+}
@@ -0,0 +1,420 @@
+//! Graph searches over implicit graphs.
+//!
+//! Graphs are described by a function which yields the neighbours of a node, or for weighted
+//! searches the neighbours together with the cost of the edge to them.
+//!
+//! Ties between paths of equal cost are broken in favour of the path that was discovered first. So
+//! yielding neighbours in the preferred order, like reading order, also makes that order decide
+//! which of the equally short paths to a node is kept. Ties between equally near goals are broken
+//! separately through a key in [`nearest`].
+
+use hashbrown::{hash_map, HashMap};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops;
+
+/// The cost of reaching a node, and the node it was reached from.
+#[derive(Debug, Clone)]
+struct Visit<N, C> {
+    cost: C,
+    parent: Option<N>,
+}
+
+/// The cheapest known paths from the start nodes of a search to every node it visited.
+#[derive(Debug, Clone)]
+pub struct Paths<N, C> {
+    visits: HashMap<N, Visit<N, C>>,
+}
+
+impl<N, C> Paths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy,
+{
+    fn new() -> Self {
+        Paths {
+            visits: HashMap::new(),
+        }
+    }
+
+    /// The number of visited nodes, including the start nodes.
+    pub fn len(&self) -> usize {
+        self.visits.len()
+    }
+
+    /// Test if no nodes were visited.
+    pub fn is_empty(&self) -> bool {
+        self.visits.is_empty()
+    }
+
+    /// Test if the given node was visited.
+    pub fn contains(&self, node: &N) -> bool {
+        self.visits.contains_key(node)
+    }
+
+    /// The cost of the cheapest path to the given node, or `None` if it wasn't visited.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.visits.get(node).map(|v| v.cost)
+    }
+
+    /// The distance to the given node, or `None` if it wasn't visited.
+    ///
+    /// Same as [`Paths::cost`], but reads better for breadth-first searches.
+    pub fn distance(&self, node: &N) -> Option<C> {
+        self.cost(node)
+    }
+
+    /// Iterate over every visited node and the cost of reaching it, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&N, C)> {
+        self.visits.iter().map(|(n, v)| (n, v.cost))
+    }
+
+    /// Reconstruct the path to the given node, starting with the start node it was reached from.
+    ///
+    /// Returns `None` if the node wasn't visited.
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        let mut path = vec![node.clone()];
+        let mut current = self.visits.get(node)?;
+
+        while let Some(parent) = current.parent.as_ref() {
+            path.push(parent.clone());
+            current = self.visits.get(parent)?;
+        }
+
+        path.reverse();
+        Some(path)
+    }
+
+    /// Record a visit if it is cheaper than any earlier one, returning `true` if it was recorded.
+    fn visit(&mut self, node: N, cost: C, parent: Option<N>) -> bool
+    where
+        C: Ord,
+    {
+        match self.visits.entry(node) {
+            hash_map::Entry::Vacant(e) => {
+                e.insert(Visit { cost, parent });
+                true
+            }
+            hash_map::Entry::Occupied(mut e) => {
+                if e.get().cost <= cost {
+                    return false;
+                }
+
+                e.insert(Visit { cost, parent });
+                true
+            }
+        }
+    }
+}
+
+/// Breadth-first search from the given start nodes, visiting every reachable node.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::search;
+///
+/// // numbers which can be reached by doubling or adding three, up to 20.
+/// let paths = search::bfs(Some(1), |n| vec![n * 2, n + 3].into_iter().filter(|n| *n <= 20));
+///
+/// assert_eq!(paths.distance(&1), Some(0));
+/// assert_eq!(paths.distance(&10), Some(3));
+/// assert_eq!(paths.path(&10), Some(vec![1, 2, 5, 10]));
+/// assert_eq!(paths.distance(&3), None);
+/// ```
+pub fn bfs<N, F, I>(starts: impl IntoIterator<Item = N>, neighbours: F) -> Paths<N, usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    bfs_until(starts, neighbours, |_| false)
+}
+
+/// Breadth-first search which stops once every node as near as the nearest goal was visited.
+///
+/// Returns the goal with the smallest key among the nearest ones, together with the paths that
+/// were explored.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::search;
+///
+/// // Both 0 and 4 can be reached in two steps, prefer the largest.
+/// let (goal, paths) =
+///     search::nearest(Some(2), |n| vec![n - 1, n + 1], |n| n % 2 == 0 && *n != 2, |n| -n)
+///         .expect("goal");
+///
+/// assert_eq!(goal, 4);
+/// assert_eq!(paths.path(&goal), Some(vec![2, 3, 4]));
+/// assert_eq!(paths.distance(&0), Some(2));
+/// ```
+pub fn nearest<N, F, I, G, K, KF>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: F,
+    mut goal: G,
+    mut key: KF,
+) -> Option<(N, Paths<N, usize>)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+    KF: FnMut(&N) -> K,
+    K: Ord,
+{
+    let mut best = None::<(K, N)>;
+
+    let paths = bfs_until(starts, neighbours, |n| {
+        if !goal(n) {
+            return false;
+        }
+
+        let k = key(n);
+
+        if best.as_ref().map(|(b, _)| k < *b).unwrap_or(true) {
+            best = Some((k, n.clone()));
+        }
+
+        true
+    });
+
+    let (_, goal) = best?;
+    Some((goal, paths))
+}
+
+/// Breadth-first search which stops after the layer where `stop` first returned `true`.
+fn bfs_until<N, F, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: F,
+    mut stop: impl FnMut(&N) -> bool,
+) -> Paths<N, usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if paths.visit(start.clone(), 0, None) {
+            queue.push_back((start, 0));
+        }
+    }
+
+    let mut limit = None;
+
+    while let Some((node, d)) = queue.pop_front() {
+        if limit.map(|limit| d > limit).unwrap_or(false) {
+            break;
+        }
+
+        if stop(&node) {
+            limit = Some(d);
+            continue;
+        }
+
+        if limit.is_some() {
+            continue;
+        }
+
+        for n in neighbours(&node) {
+            if paths.visit(n.clone(), d + 1, Some(node.clone())) {
+                queue.push_back((n, d + 1));
+            }
+        }
+    }
+
+    paths
+}
+
+/// Dijkstra's algorithm from the given start nodes, visiting every reachable node.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::search;
+///
+/// let edges = |n: &char| match *n {
+///     'a' => vec![('b', 7), ('c', 2)],
+///     'c' => vec![('b', 3), ('d', 10)],
+///     'b' => vec![('d', 1)],
+///     _ => vec![],
+/// };
+///
+/// let paths = search::dijkstra(Some('a'), edges);
+/// assert_eq!(paths.cost(&'d'), Some(6));
+/// assert_eq!(paths.path(&'d'), Some(vec!['a', 'c', 'b', 'd']));
+/// ```
+pub fn dijkstra<N, C, F, I>(starts: impl IntoIterator<Item = N>, edges: F) -> Paths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + ops::Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    weighted(starts, edges, |_| C::default(), |_| false).0
+}
+
+/// A* search from the given start node to the nearest goal.
+///
+/// The heuristic must never overestimate the cost of reaching a goal. Returns the goal that was
+/// reached and the cost of reaching it, together with the paths that were explored.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::search;
+///
+/// let goal = (3i64, 2i64);
+/// let neighbours = |&(x, y): &(i64, i64)| vec![((x + 1, y), 1), ((x, y + 1), 1), ((x - 1, y), 1)];
+/// let heuristic = |&(x, y): &(i64, i64)| (goal.0 - x).abs() + (goal.1 - y).abs();
+///
+/// let (found, cost, paths) =
+///     search::astar((0, 0), neighbours, heuristic, |n| *n == goal).expect("goal");
+///
+/// assert_eq!((found, cost), (goal, 5));
+/// assert_eq!(paths.path(&found).map(|p| p.len()), Some(6));
+/// ```
+pub fn astar<N, C, F, I, H, G>(
+    start: N,
+    edges: F,
+    heuristic: H,
+    goal: G,
+) -> Option<(N, C, Paths<N, C>)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + ops::Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let (paths, found) = weighted(Some(start), edges, heuristic, goal);
+    let found = found?;
+    let cost = paths.cost(&found)?;
+    Some((found, cost, paths))
+}
+
+/// Shared implementation of Dijkstra and A*, which stops at the first goal popped.
+fn weighted<N, C, F, I, H, G>(
+    starts: impl IntoIterator<Item = N>,
+    mut edges: F,
+    mut heuristic: H,
+    mut goal: G,
+) -> (Paths<N, C>, Option<N>)
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + ops::Add<Output = C>,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut paths = Paths::new();
+    // NB: nodes are kept out of the heap so they don't need to be `Ord`. The sequence number
+    // breaks ties in favour of the node that was queued first.
+    let mut nodes = Vec::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if paths.visit(start.clone(), C::default(), None) {
+            heap.push(Reverse((heuristic(&start), nodes.len())));
+            nodes.push((start, C::default()));
+        }
+    }
+
+    while let Some(Reverse((_, index))) = heap.pop() {
+        let (node, cost) = nodes[index].clone();
+
+        // NB: skip stale entries for nodes that were since reached more cheaply.
+        if paths.cost(&node).map(|c| c < cost).unwrap_or(false) {
+            continue;
+        }
+
+        if goal(&node) {
+            return (paths, Some(node));
+        }
+
+        for (n, c) in edges(&node) {
+            let cost = cost + c;
+
+            if paths.visit(n.clone(), cost, Some(node.clone())) {
+                heap.push(Reverse((cost + heuristic(&n), nodes.len())));
+                nodes.push((n, cost));
+            }
+        }
+    }
+
+    (paths, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unreachable() {
+        // NB: odd numbers can never be reached from an even start.
+        let neighbours = |n: &i64| vec![n - 2, n + 2].into_iter().filter(|n| n.abs() <= 10);
+
+        let paths = bfs(Some(0), neighbours);
+        assert_eq!(paths.len(), 11);
+        assert_eq!(paths.path(&3), None);
+        assert!(nearest(Some(0), neighbours, |n| *n == 3, |n| *n).is_none());
+
+        let edges = |n: &i64| neighbours(n).map(|n| (n, 1));
+        assert_eq!(dijkstra(Some(0), edges).cost(&3), None);
+        assert!(astar(0, edges, |_| 0, |n| *n == 3).is_none());
+    }
+
+    #[test]
+    fn test_tie_in_reading_order() {
+        // NB: positions are `(y, x)`, so that they sort in reading order, and neighbours are
+        // visited in reading order too.
+        let neighbours = |&(y, x): &(i64, i64)| {
+            vec![(y - 1, x), (y, x - 1), (y, x + 1), (y + 1, x)]
+                .into_iter()
+                .filter(|(y, x)| y.abs() <= 2 && x.abs() <= 2)
+        };
+
+        // all four squares diagonal to the start are equally near, and the top-left one comes
+        // first in reading order.
+        let diagonal = |&(y, x): &(i64, i64)| y != 0 && x != 0;
+        let (goal, paths) = nearest(Some((0, 0)), neighbours, diagonal, |p| *p).expect("goal");
+
+        assert_eq!(goal, (-1, -1));
+        assert_eq!(paths.distance(&goal), Some(2));
+
+        // of the two equally short paths to it, the one whose first step is first in reading order
+        // is kept.
+        assert_eq!(paths.path(&(1, 1)), Some(vec![(0, 0), (0, 1), (1, 1)]));
+    }
+
+    #[test]
+    fn test_astar_skips_stale_entries() {
+        let mut expanded = HashMap::<char, usize>::new();
+
+        // NB: `b` is first queued through the expensive direct edge, and then again more cheaply
+        // through `a`. The expensive entry is still in the heap once `b` has been expanded.
+        let edges = |n: &char| {
+            *expanded.entry(*n).or_default() += 1;
+
+            match *n {
+                's' => vec![('a', 1), ('b', 5)],
+                'a' => vec![('b', 1)],
+                'b' => vec![('z', 10)],
+                _ => vec![],
+            }
+        };
+
+        let (goal, cost, paths) = astar('s', edges, |_| 0, |n| *n == 'z').expect("goal");
+
+        assert_eq!((goal, cost), ('z', 12));
+        assert_eq!(paths.path(&'z'), Some(vec!['s', 'a', 'b', 'z']));
+        assert_eq!(expanded.get(&'b'), Some(&1));
+    }
+}
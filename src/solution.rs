@@ -0,0 +1,277 @@
+use crate::bench::{self, Measurement, Options, Variant};
+use crate::gen::Rng;
+use crate::{bail, Error, Input, ParseError, ValidationError};
+use std::any::Any;
+use std::fmt;
+use std::str;
+use std::time::{Duration, Instant};
+
+/// A part of a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    /// Both parts, in order.
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Part::One => "1".fmt(fmt),
+            Part::Two => "2".fmt(fmt),
+        }
+    }
+}
+
+impl str::FromStr for Part {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            other => bail!("bad part `{}`, expected `1` or `2`", other),
+        }
+    }
+}
+
+/// The solution to a single day.
+///
+/// The input is parsed once, and the parsed representation is then handed to each part.
+pub trait Solution {
+    /// The parsed input.
+    type Input;
+    /// The answer to the first part.
+    type Part1: fmt::Display;
+    /// The answer to the second part.
+    type Part2: fmt::Display;
+
+    /// Parse the input.
+    fn parse(input: &str) -> Result<Self::Input, Error>;
+
+    /// Check the whole input before it's parsed, and report every problem found in it.
+    ///
+    /// Parsing stops at the first problem, and some problems only surface while solving. Days
+    /// with such inputs check for them here instead, so that they're all reported with their
+    /// location before any solving begins.
+    fn validate(_input: &str) -> Vec<ParseError> {
+        Vec::new()
+    }
+
+    /// Solve the first part.
+    fn part1(input: &Self::Input) -> Result<Self::Part1, Error>;
+
+    /// Solve the second part.
+    fn part2(input: &Self::Input) -> Result<Self::Part2, Error>;
+
+    /// Alternative implementations of the parts, which are benchmarked against them.
+    fn variants() -> Vec<Variant<Self::Input>> {
+        Vec::new()
+    }
+
+    /// Generate a random input of the given size, or `None` if the day doesn't support it.
+    ///
+    /// See [`gen`](crate::gen).
+    fn generate(_rng: &mut Rng, _size: usize) -> Option<String> {
+        None
+    }
+}
+
+/// Parse the input and solve both parts of the given solution.
+///
+/// Parse errors are annotated with the name of the input.
+pub fn solve<S>(input: &Input) -> Result<(S::Part1, S::Part2), Error>
+where
+    S: Solution,
+{
+    let input = validate::<S>(input.as_str())
+        .and_then(|()| S::parse(input.as_str()))
+        .map_err(|e| ParseError::attach_name(e, input.name()))?;
+    Ok((S::part1(&input)?, S::part2(&input)?))
+}
+
+/// Validate the input of the given solution, failing with every problem found in it.
+pub(crate) fn validate<S>(input: &str) -> Result<(), Error>
+where
+    S: Solution,
+{
+    let errors = S::validate(input);
+
+    if !errors.is_empty() {
+        return Err(ValidationError::new(errors).into());
+    }
+
+    Ok(())
+}
+
+/// The answer to a single part of a puzzle.
+#[derive(Debug)]
+pub struct Answer {
+    /// The part that was solved.
+    pub part: Part,
+    /// The displayed answer, or the error raised while solving.
+    pub value: Result<String, Error>,
+    /// How long it took to solve the part.
+    pub duration: Duration,
+}
+
+/// An input parsed by a [`Day`], which its parts can be run against one at a time.
+///
+/// See [`Day::parse`].
+pub struct Parsed(Box<dyn Any>);
+
+impl fmt::Debug for Parsed {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("Parsed").finish()
+    }
+}
+
+/// Type-erased benchmark of a solution.
+type BenchFn = fn(&str, &[Part], &Options) -> Result<Vec<Measurement>, Error>;
+
+/// A registered day, which can be run without knowing the type of its solution.
+#[derive(Clone, Copy)]
+pub struct Day {
+    /// The day of the puzzle.
+    pub day: u32,
+    /// The name of the bundled input.
+    pub name: &'static str,
+    /// The bundled input.
+    pub bundled: &'static str,
+    run: fn(&str, &[Part]) -> Result<Vec<Answer>, Error>,
+    parse: fn(&str) -> Result<Parsed, Error>,
+    run_part: fn(&Parsed, Part) -> Answer,
+    bench: BenchFn,
+    generate: fn(&mut Rng, usize) -> Option<String>,
+}
+
+impl Day {
+    /// Register a day with the given solution.
+    pub fn new<S>(day: u32, name: &'static str, bundled: &'static str) -> Day
+    where
+        S: Solution,
+        S::Input: 'static,
+    {
+        Day {
+            day,
+            name,
+            bundled,
+            run: run::<S>,
+            parse: parse::<S>,
+            run_part: run_part::<S>,
+            bench: bench::bench::<S>,
+            generate: S::generate,
+        }
+    }
+
+    /// The input bundled with the crate for this day.
+    pub fn input(&self) -> Input {
+        Input::bundled(self.name, self.bundled)
+    }
+
+    /// Generate a random input from the given seed and size, if the day supports it.
+    pub fn generate(&self, seed: u64, size: usize) -> Option<Input> {
+        let data = (self.generate)(&mut Rng::new(seed), size)?;
+        Some(Input::generated(seed, size, data))
+    }
+
+    /// Run the given parts against the input.
+    ///
+    /// Problems found while validating or parsing the input are returned immediately, while errors
+    /// in each part are reported through their corresponding [`Answer`].
+    pub fn run(&self, input: &Input, parts: &[Part]) -> Result<Vec<Answer>, Error> {
+        (self.run)(input.as_str(), parts).map_err(|e| ParseError::attach_name(e, input.name()))
+    }
+
+    /// Validate and parse the input, so that parts can be run against it through
+    /// [`Day::run_part`].
+    pub fn parse(&self, input: &Input) -> Result<Parsed, Error> {
+        (self.parse)(input.as_str()).map_err(|e| ParseError::attach_name(e, input.name()))
+    }
+
+    /// Run a single part against an input parsed by this day.
+    ///
+    /// # Panics
+    ///
+    /// If the input was parsed by a different day.
+    pub fn run_part(&self, input: &Parsed, part: Part) -> Answer {
+        (self.run_part)(input, part)
+    }
+
+    /// Benchmark parsing and the given parts against the input, including all their variants.
+    pub fn bench(
+        &self,
+        input: &Input,
+        parts: &[Part],
+        options: &Options,
+    ) -> Result<Vec<Measurement>, Error> {
+        (self.bench)(input.as_str(), parts, options)
+            .map_err(|e| ParseError::attach_name(e, input.name()))
+    }
+}
+
+impl fmt::Debug for Day {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Day")
+            .field("day", &self.day)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+fn run<S>(input: &str, parts: &[Part]) -> Result<Vec<Answer>, Error>
+where
+    S: Solution,
+{
+    validate::<S>(input)?;
+    let input = S::parse(input)?;
+    Ok(parts
+        .iter()
+        .map(|part| answer::<S>(&input, *part))
+        .collect())
+}
+
+fn parse<S>(input: &str) -> Result<Parsed, Error>
+where
+    S: Solution,
+    S::Input: 'static,
+{
+    validate::<S>(input)?;
+    Ok(Parsed(Box::new(S::parse(input)?)))
+}
+
+fn run_part<S>(input: &Parsed, part: Part) -> Answer
+where
+    S: Solution,
+    S::Input: 'static,
+{
+    let input = input
+        .0
+        .downcast_ref::<S::Input>()
+        .expect("input parsed by a different day");
+
+    answer::<S>(input, part)
+}
+
+/// Solve a single part, timing how long it takes.
+fn answer<S>(input: &S::Input, part: Part) -> Answer
+where
+    S: Solution,
+{
+    let start = Instant::now();
+
+    let value = match part {
+        Part::One => S::part1(input).map(|a| a.to_string()),
+        Part::Two => S::part2(input).map(|a| a.to_string()),
+    };
+
+    Answer {
+        part,
+        value,
+        duration: start.elapsed(),
+    }
+}
@@ -0,0 +1,841 @@
+//! Shared visualizations for simulations.
+//!
+//! Simulations draw [`Frame`]s through [`Visuals`], which hands them to a [`Backend`] and takes
+//! care of pacing: how long to sleep between frames, only drawing every n frames, and stepping
+//! interactively.
+//!
+//! In interactive mode, space or enter steps to the next frame, `c` continues without stepping,
+//! and `q` quits by returning a [`Quit`] error from [`Visuals::draw`]. Instead of being typed, these
+//! keys can also be fed from a [`Script`].
+
+use crate::events::JsonString;
+use crate::{bail, Context, Error, ParseError};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::env;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// How a cell in a frame is styled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Style {
+    #[default]
+    Normal,
+    /// Reversed colors, to make something stand out.
+    Standout,
+    Underline,
+    /// Blinking and reversed colors, for results.
+    Highlight,
+}
+
+/// A single character in a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub c: char,
+    pub style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            c: ' ',
+            style: Style::Normal,
+        }
+    }
+}
+
+/// A single frame of text to draw.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::visuals::{Frame, Style};
+///
+/// let mut frame = Frame::new();
+/// frame.print(0, 2, "ab\ncd");
+/// frame.print_styled(1, 0, Style::Standout, ">");
+/// frame.line("done");
+///
+/// assert_eq!(frame.to_string(), "  ab\n> cd\ndone\n");
+/// assert_eq!(frame.rows()[1][0].style, Style::Standout);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Frame {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Frame {
+    /// Construct a new empty frame.
+    pub fn new() -> Frame {
+        Frame::default()
+    }
+
+    /// Print text starting at the given row and column.
+    ///
+    /// Newlines in the text continue on the next row, at the same column.
+    pub fn print(&mut self, row: usize, col: usize, text: impl fmt::Display) {
+        self.print_styled(row, col, Style::Normal, text);
+    }
+
+    /// Print styled text starting at the given row and column.
+    pub fn print_styled(&mut self, row: usize, col: usize, style: Style, text: impl fmt::Display) {
+        for (n, line) in text.to_string().split('\n').enumerate() {
+            let row = row + n;
+
+            if self.rows.len() <= row {
+                self.rows.resize_with(row + 1, Vec::new);
+            }
+
+            let cells = &mut self.rows[row];
+
+            for (i, c) in line.chars().enumerate() {
+                let col = col + i;
+
+                if cells.len() <= col {
+                    cells.resize_with(col + 1, Cell::default);
+                }
+
+                cells[col] = Cell { c, style };
+            }
+        }
+    }
+
+    /// Print text on a new row below everything else.
+    pub fn line(&mut self, text: impl fmt::Display) {
+        let row = self.rows.len();
+        self.print(row, 0, text);
+    }
+
+    /// The number of rows in the frame.
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The rows of the frame.
+    pub fn rows(&self) -> &[Vec<Cell>] {
+        &self.rows
+    }
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.rows {
+            for cell in row {
+                write!(fmt, "{}", cell.c)?;
+            }
+
+            writeln!(fmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Something frames can be drawn to.
+pub trait Backend {
+    /// Prepare to draw, called before the first frame.
+    fn setup(&mut self) -> Result<(), Error>;
+
+    /// Draw a frame.
+    fn draw(&mut self, frame: &Frame) -> Result<(), Error>;
+
+    /// Wait for the given duration between two frames.
+    fn pause(&mut self, duration: Duration) -> Result<(), Error>;
+
+    /// Read the next key, or `None` if the backend doesn't take input.
+    fn key(&mut self) -> Result<Option<char>, Error>;
+
+    /// Show a final message and tear down.
+    ///
+    /// Backends which take input wait for the user to acknowledge the message if `wait` is set.
+    fn done(&mut self, message: &str, wait: bool) -> Result<(), Error>;
+}
+
+/// Error returned from [`Visuals::draw`] when the user asks to quit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quit;
+
+impl fmt::Display for Quit {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "quit by user".fmt(fmt)
+    }
+}
+
+impl error::Error for Quit {}
+
+/// Draws frames from a simulation to a backend, if any.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+/// use aoc2018::visuals::{Recording, Visuals};
+/// use std::time::Duration;
+///
+/// fn main() -> Result<(), Error> {
+///     let recording = Recording::default();
+///     let mut visuals = Visuals::recorder(&recording).sleep(Duration::from_millis(10)).every(2);
+///
+///     for n in 0..5 {
+///         visuals.draw(|f| f.line(format!("frame {}", n)))?;
+///     }
+///
+///     visuals.done("done")?;
+///
+///     let frames = recording.frames();
+///     assert_eq!(frames.len(), 4);
+///     assert_eq!(frames[1].0, Duration::from_millis(10));
+///     assert_eq!(frames[1].1.to_string(), "frame 2\n");
+///     assert_eq!(frames[3].1.to_string(), "frame 4\ndone\n");
+///     Ok(())
+/// }
+/// ```
+pub struct Visuals {
+    backend: Option<Box<dyn Backend>>,
+    sleep: Duration,
+    every: usize,
+    interactive: bool,
+    count: usize,
+    started: bool,
+    last: Option<Frame>,
+    script: Option<Script>,
+}
+
+impl Visuals {
+    /// Visuals which don't draw anything.
+    pub fn noop() -> Visuals {
+        Visuals {
+            backend: None,
+            sleep: Duration::from_millis(50),
+            every: 1,
+            interactive: false,
+            count: 0,
+            started: false,
+            last: None,
+            script: None,
+        }
+    }
+
+    /// Visuals which draw to the given backend.
+    pub fn new(backend: impl Backend + 'static) -> Visuals {
+        Visuals {
+            backend: Some(Box::new(backend)),
+            ..Visuals::noop()
+        }
+    }
+
+    /// Visuals which draw to the terminal through ncurses.
+    pub fn ncurses() -> Visuals {
+        Visuals::new(Ncurses::default())
+    }
+
+    /// Visuals which draw to stdout using plain ANSI escape codes.
+    pub fn ansi() -> Visuals {
+        Visuals::new(Ansi)
+    }
+
+    /// Visuals which record every frame to an asciicast file at the given path.
+    pub fn asciicast(path: impl AsRef<Path>) -> Visuals {
+        Visuals::new(Asciicast::new(path))
+    }
+
+    /// Visuals which record every frame to the given recording.
+    pub fn recorder(recording: &Recording) -> Visuals {
+        Visuals::new(recording.clone())
+    }
+
+    /// How long to sleep after each frame, defaults to 50ms.
+    pub fn sleep(mut self, sleep: Duration) -> Self {
+        self.sleep = sleep;
+        self
+    }
+
+    /// Only draw every n frames.
+    pub fn every(mut self, every: usize) -> Self {
+        self.every = every.max(1);
+        self
+    }
+
+    /// Wait for a key after each frame.
+    pub fn interactive(mut self) -> Self {
+        self.interactive = true;
+        self
+    }
+
+    /// Read keys from the given script while it lasts, before reading them from the backend.
+    pub fn script(mut self, script: Script) -> Self {
+        self.script = Some(script);
+        self
+    }
+
+    /// Test if the visuals draw anything.
+    pub fn is_enabled(&self) -> bool {
+        self.backend.is_some()
+    }
+
+    /// Draw a frame, which is built by the given function.
+    ///
+    /// The function is only called if the frame is actually drawn.
+    pub fn draw(&mut self, build: impl FnOnce(&mut Frame)) -> Result<(), Error> {
+        let backend = match self.backend.as_mut() {
+            Some(backend) => backend,
+            None => return Ok(()),
+        };
+
+        let count = self.count;
+        self.count += 1;
+
+        if !count.is_multiple_of(self.every) {
+            return Ok(());
+        }
+
+        if !self.started {
+            backend.setup()?;
+            self.started = true;
+        }
+
+        let mut frame = Frame::new();
+        build(&mut frame);
+        backend.draw(&frame)?;
+        self.last = Some(frame);
+
+        if !self.interactive {
+            return backend.pause(self.sleep);
+        }
+
+        loop {
+            let key = match self.script.as_mut().and_then(|s| s.keys.pop_front()) {
+                Some(key) => Some(key),
+                None => backend.key()?,
+            };
+
+            match key {
+                // NB: nothing to wait for.
+                None => return backend.pause(self.sleep),
+                Some(' ') | Some('\n') => return Ok(()),
+                Some('c') => {
+                    self.interactive = false;
+                    return Ok(());
+                }
+                Some('q') => return Err(Error::from(Quit)),
+                Some(_) => {}
+            }
+        }
+    }
+
+    /// Show a final message below the last frame, and tear down the backend.
+    ///
+    /// Backends which take input wait for a key before tearing down, unless the keys come from a
+    /// script.
+    pub fn done(&mut self, message: impl fmt::Display) -> Result<(), Error> {
+        let backend = match self.backend.as_mut() {
+            Some(backend) => backend,
+            None => return Ok(()),
+        };
+
+        if !self.started {
+            backend.setup()?;
+        }
+
+        self.started = false;
+
+        let mut frame = self.last.take().unwrap_or_default();
+        frame.line(message.to_string());
+        backend.draw(&frame)?;
+        backend.done(&message.to_string(), self.script.is_none())
+    }
+}
+
+impl Default for Visuals {
+    fn default() -> Self {
+        Visuals::noop()
+    }
+}
+
+/// Environment variable consulted for a script of keys to feed interactive visuals.
+pub const SCRIPT_ENV: &str = "AOC_SCRIPT";
+
+/// A script of keys to feed to interactive visuals.
+///
+/// A script is a list of commands separated by commas or newlines:
+///
+/// * `step` or `step <n>` steps to the next frame, or n frames ahead.
+/// * `continue` runs to the end without stepping.
+/// * `quit` quits.
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+/// use aoc2018::visuals::{Quit, Recording, Script, Visuals};
+///
+/// fn main() -> Result<(), Error> {
+///     let script = Script::parse("step 2, quit")?;
+///     assert_eq!(script.keys().collect::<String>(), "  q");
+///
+///     let recording = Recording::default();
+///     let mut visuals = Visuals::recorder(&recording).interactive().script(script);
+///
+///     let e = (0..10).try_for_each(|n| visuals.draw(|f| f.line(n))).unwrap_err();
+///     assert!(e.is::<Quit>());
+///     assert_eq!(recording.frames().len(), 3);
+///
+///     let e = Script::parse("step\nsprint").unwrap_err().downcast::<ParseError>()?;
+///     assert_eq!((e.line(), e.columns()), (2, 1..7));
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Script {
+    keys: VecDeque<char>,
+}
+
+impl Script {
+    /// Parse a script.
+    pub fn parse(input: &str) -> Result<Script, Error> {
+        let mut keys = VecDeque::new();
+
+        for command in input.split([',', '\n']) {
+            let trimmed = command.trim();
+
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let start = trimmed.as_ptr() as usize - input.as_ptr() as usize;
+            let span = start..start + trimmed.len();
+            let mut parts = trimmed.split_whitespace();
+
+            let (key, count) = match (parts.next(), parts.next(), parts.next()) {
+                (Some("step"), None, None) => (' ', 1),
+                (Some("step"), Some(n), None) => match str::parse::<usize>(n) {
+                    Ok(n) => (' ', n),
+                    Err(e) => {
+                        let message = format!("bad step count `{}`: {}", n, e);
+                        return Err(ParseError::locate(input, span, message).into());
+                    }
+                },
+                (Some("continue"), None, None) => ('c', 1),
+                (Some("quit"), None, None) => ('q', 1),
+                _ => {
+                    let message = format!("unknown command `{}`", trimmed);
+                    return Err(ParseError::locate(input, span, message).into());
+                }
+            };
+
+            keys.extend(std::iter::repeat_n(key, count));
+        }
+
+        Ok(Script { keys })
+    }
+
+    /// Load a script from the given file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Script, Error> {
+        let path = path.as_ref();
+        let input = fs::read_to_string(path)
+            .with_context(|| format!("failed to read script: {}", path.display()))?;
+        Script::parse(&input)
+    }
+
+    /// Get the script from [`SCRIPT_ENV`], or `None` if it isn't set.
+    ///
+    /// The variable either names a file to load the script from, or is the script itself.
+    pub fn from_env() -> Result<Option<Script>, Error> {
+        let value = match env::var_os(SCRIPT_ENV) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        if Path::new(&value).is_file() {
+            return Script::load(value).map(Some);
+        }
+
+        match value.to_str() {
+            Some(script) => Script::parse(script).map(Some),
+            None => bail!("{} is not valid utf-8", SCRIPT_ENV),
+        }
+    }
+
+    /// The keys which are left in the script.
+    pub fn keys(&self) -> impl Iterator<Item = char> + '_ {
+        self.keys.iter().cloned()
+    }
+}
+
+/// Draws to the terminal through ncurses.
+#[derive(Default)]
+pub struct Ncurses {
+    active: bool,
+}
+
+impl Backend for Ncurses {
+    fn setup(&mut self) -> Result<(), Error> {
+        ncurses::initscr();
+        ncurses::noecho();
+        ncurses::curs_set(ncurses::CURSOR_VISIBILITY::CURSOR_INVISIBLE);
+        self.active = true;
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &Frame) -> Result<(), Error> {
+        ncurses::erase();
+
+        for (y, row) in frame.rows().iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if cell.c == ' ' && cell.style == Style::Normal {
+                    continue;
+                }
+
+                let attrs = match cell.style {
+                    Style::Normal => 0,
+                    Style::Standout => ncurses::A_STANDOUT(),
+                    Style::Underline => ncurses::A_UNDERLINE(),
+                    Style::Highlight => ncurses::A_BLINK() | ncurses::A_STANDOUT(),
+                };
+
+                ncurses::attron(attrs);
+                ncurses::mvaddstr(y as i32, x as i32, &cell.c.to_string());
+                ncurses::attroff(attrs);
+            }
+        }
+
+        ncurses::refresh();
+        Ok(())
+    }
+
+    fn pause(&mut self, duration: Duration) -> Result<(), Error> {
+        if duration > Duration::default() {
+            std::thread::sleep(duration);
+        }
+
+        Ok(())
+    }
+
+    fn key(&mut self) -> Result<Option<char>, Error> {
+        Ok(std::char::from_u32(ncurses::getch() as u32))
+    }
+
+    fn done(&mut self, message: &str, wait: bool) -> Result<(), Error> {
+        if wait {
+            // NB: the prompt continues the line with the message.
+            let separator = if message.is_empty() { "" } else { ", " };
+            ncurses::addstr(&format!("{}press [enter] to exit...", separator));
+            ncurses::refresh();
+
+            while ncurses::getch() != 10 {}
+        }
+
+        ncurses::endwin();
+        self.active = false;
+        Ok(())
+    }
+}
+
+impl Drop for Ncurses {
+    fn drop(&mut self) {
+        // NB: restore the terminal if we exit early, like through an error.
+        if self.active {
+            ncurses::endwin();
+        }
+    }
+}
+
+/// Draws to stdout using plain ANSI escape codes, reading keys as lines from stdin.
+pub struct Ansi;
+
+impl Backend for Ansi {
+    fn setup(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &Frame) -> Result<(), Error> {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        write_ansi(&mut out, frame, "\n")?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn pause(&mut self, duration: Duration) -> Result<(), Error> {
+        if duration > Duration::default() {
+            std::thread::sleep(duration);
+        }
+
+        Ok(())
+    }
+
+    fn key(&mut self) -> Result<Option<char>, Error> {
+        let mut line = String::new();
+
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        Ok(line.chars().next())
+    }
+
+    fn done(&mut self, _: &str, _: bool) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Write a frame which clears the screen using ANSI escape codes, ending each row with `newline`.
+fn write_ansi(mut out: impl Write, frame: &Frame, newline: &str) -> io::Result<()> {
+    write!(out, "\x1b[2J\x1b[H")?;
+
+    for row in frame.rows() {
+        let mut style = Style::Normal;
+
+        for cell in row {
+            if cell.style != style {
+                style = cell.style;
+
+                match style {
+                    Style::Normal => write!(out, "\x1b[0m")?,
+                    Style::Standout => write!(out, "\x1b[0;7m")?,
+                    Style::Underline => write!(out, "\x1b[0;4m")?,
+                    Style::Highlight => write!(out, "\x1b[0;5;7m")?,
+                }
+            }
+
+            write!(out, "{}", cell.c)?;
+        }
+
+        if style != Style::Normal {
+            write!(out, "\x1b[0m")?;
+        }
+
+        write!(out, "{}", newline)?;
+    }
+
+    Ok(())
+}
+
+/// Records frames to an [asciicast v2] file, which can be replayed with `asciinema play`.
+///
+/// Like a [`Recording`], time is not measured but advances by the configured sleep after each
+/// frame. Since the header needs the size of the terminal, frames are kept in memory and the file
+/// is written once the visuals are done, or when the backend is dropped.
+///
+/// [asciicast v2]: https://docs.asciinema.org/manual/asciicast/v2/
+///
+/// # Examples
+///
+/// ```rust
+/// use aoc2018::*;
+/// use aoc2018::visuals::Visuals;
+/// use std::time::Duration;
+///
+/// fn main() -> Result<(), Error> {
+///     let path = std::env::temp_dir().join("aoc2018-asciicast-doctest.cast");
+///     let mut visuals = Visuals::asciicast(&path).sleep(Duration::from_millis(250));
+///
+///     visuals.draw(|f| f.line("first"))?;
+///     visuals.draw(|f| f.line("\"second\""))?;
+///     visuals.done("done")?;
+///
+///     let cast = std::fs::read_to_string(&path)?;
+///     let lines = cast.lines().collect::<Vec<_>>();
+///
+///     assert_eq!(lines[0], r#"{"version": 2, "width": 8, "height": 2}"#);
+///     assert_eq!(lines[1], r#"[0.000000, "o", "\u001b[2J\u001b[Hfirst\r\n"]"#);
+///     assert_eq!(lines[2], r#"[0.250000, "o", "\u001b[2J\u001b[H\"second\"\r\n"]"#);
+///     assert_eq!(lines.len(), 4);
+///     Ok(())
+/// }
+/// ```
+pub struct Asciicast {
+    path: PathBuf,
+    clock: Duration,
+    width: usize,
+    height: usize,
+    events: Vec<(Duration, String)>,
+}
+
+impl Asciicast {
+    /// Construct a recorder which writes to the given path.
+    pub fn new(path: impl AsRef<Path>) -> Asciicast {
+        Asciicast {
+            path: path.as_ref().to_owned(),
+            clock: Duration::default(),
+            width: 0,
+            height: 0,
+            events: Vec::new(),
+        }
+    }
+
+    /// Write the recorded events, if there are any.
+    fn write(&mut self) -> Result<(), Error> {
+        if self.events.is_empty() {
+            return Ok(());
+        }
+
+        let mut out = io::BufWriter::new(fs::File::create(&self.path)?);
+
+        writeln!(
+            out,
+            "{{\"version\": 2, \"width\": {}, \"height\": {}}}",
+            self.width.max(1),
+            self.height.max(1)
+        )?;
+
+        for (time, data) in self.events.drain(..) {
+            writeln!(
+                out,
+                "[{}.{:06}, \"o\", {}]",
+                time.as_secs(),
+                time.subsec_micros(),
+                JsonString(&data)
+            )?;
+        }
+
+        out.flush()?;
+        Ok(())
+    }
+}
+
+impl Backend for Asciicast {
+    fn setup(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &Frame) -> Result<(), Error> {
+        // NB: anything that isn't ASCII is counted as double width, since that covers emoji.
+        let width = frame
+            .rows()
+            .iter()
+            .map(|row| row.iter().map(|c| if c.c.is_ascii() { 1 } else { 2 }).sum())
+            .max()
+            .unwrap_or_default();
+
+        self.width = usize::max(self.width, width);
+        self.height = usize::max(self.height, frame.height());
+
+        let mut data = Vec::new();
+        write_ansi(&mut data, frame, "\r\n")?;
+        self.events.push((self.clock, String::from_utf8(data)?));
+        Ok(())
+    }
+
+    fn pause(&mut self, duration: Duration) -> Result<(), Error> {
+        self.clock += duration;
+        Ok(())
+    }
+
+    fn key(&mut self) -> Result<Option<char>, Error> {
+        Ok(None)
+    }
+
+    fn done(&mut self, _: &str, _: bool) -> Result<(), Error> {
+        self.write()
+    }
+}
+
+impl Drop for Asciicast {
+    fn drop(&mut self) {
+        // NB: keep what was recorded if we exit early, like through an error.
+        let _ = self.write();
+    }
+}
+
+/// Frames recorded by a recorder, together with when they were drawn.
+///
+/// Time is not measured, but advances by the configured sleep after each frame. This is a handle
+/// which can be cloned, where all clones refer to the same recording.
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    inner: Rc<RefCell<RecordingInner>>,
+}
+
+#[derive(Debug, Default)]
+struct RecordingInner {
+    clock: Duration,
+    frames: Vec<(Duration, Frame)>,
+}
+
+impl Recording {
+    /// Get a copy of all recorded frames.
+    pub fn frames(&self) -> Vec<(Duration, Frame)> {
+        self.inner.borrow().frames.clone()
+    }
+}
+
+impl Backend for Recording {
+    fn setup(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &Frame) -> Result<(), Error> {
+        let mut inner = self.inner.borrow_mut();
+        let clock = inner.clock;
+        inner.frames.push((clock, frame.clone()));
+        Ok(())
+    }
+
+    fn pause(&mut self, duration: Duration) -> Result<(), Error> {
+        self.inner.borrow_mut().clock += duration;
+        Ok(())
+    }
+
+    fn key(&mut self) -> Result<Option<char>, Error> {
+        Ok(None)
+    }
+
+    fn done(&mut self, _: &str, _: bool) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A backend which records whether it was asked to wait when done.
+    #[derive(Clone, Default)]
+    struct Waits(Rc<RefCell<Vec<bool>>>);
+
+    impl Backend for Waits {
+        fn setup(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn draw(&mut self, _: &Frame) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn pause(&mut self, _: Duration) -> Result<(), Error> {
+            Ok(())
+        }
+
+        fn key(&mut self) -> Result<Option<char>, Error> {
+            Ok(Some('\n'))
+        }
+
+        fn done(&mut self, _: &str, wait: bool) -> Result<(), Error> {
+            self.0.borrow_mut().push(wait);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_done_waits_unless_scripted() -> Result<(), Error> {
+        let waits = Waits::default();
+
+        let mut visuals = Visuals::new(waits.clone()).interactive();
+        visuals.draw(|f| f.line("frame"))?;
+        visuals.done("Result is 1")?;
+
+        let script = Script::parse("continue")?;
+        let mut visuals = Visuals::new(waits.clone()).interactive().script(script);
+        visuals.draw(|f| f.line("frame"))?;
+        visuals.done("Result is 1")?;
+
+        assert_eq!(*waits.0.borrow(), vec![true, false]);
+        Ok(())
+    }
+}